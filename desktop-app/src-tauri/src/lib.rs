@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use serialport::SerialPort;
 use base64::Engine as _;
@@ -7,14 +7,86 @@ use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::{fs::OpenOptions};
-use std::sync::{mpsc, Arc, Mutex};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, RwLock};
 use std::thread;
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 const SERIAL_EVENT: &str = "serial_line";
+const SERIAL_READY_EVENT: &str = "serial_ready";
+const XMODEM_PROGRESS_EVENT: &str = "xmodem_progress";
+const SERIAL_PROGRESS_EVENT: &str = "serial_progress";
+const SERIAL_BAUD_MISMATCH_EVENT: &str = "serial_baud_mismatch";
+const SERIAL_REPEAT_EVENT: &str = "serial_repeat";
+const SERIAL_RECONNECTING_EVENT: &str = "serial_reconnecting";
+const SERIAL_RECONNECTED_EVENT: &str = "serial_reconnected";
+const SERIAL_RECONNECT_FAILED_EVENT: &str = "serial_reconnect_failed";
+const SERIAL_RECONNECT_INITIAL_MS: u64 = 250;
+const SERIAL_RECONNECT_MAX_MS: u64 = 5_000;
+const SERIAL_PORTS_SNAPSHOT_EVENT: &str = "serial_ports_snapshot";
+const SERIAL_PORT_ADDED_EVENT: &str = "serial_port_added";
+const SERIAL_PORT_REMOVED_EVENT: &str = "serial_port_removed";
+const PORT_WATCH_INTERVAL_MS: u64 = 1_000;
+// A poll gap this much longer than the interval (by wall clock) means the machine slept.
+const RESUME_DETECT_SLACK_MS: u64 = 5_000;
+const EVENT_LOG_CAPACITY: usize = 1_000;
+const MAX_BENCHMARK_PAYLOAD: usize = 4_096;
+const MAX_BENCHMARK_ITERATIONS: u32 = 1_000;
+const BENCHMARK_ECHO_TIMEOUT_MS: u64 = 2_000;
+// Redundant stop writes for send_raw_mecanum over lossy links.
+const MAX_STOP_REPEAT: u32 = 10;
+const MAX_STOP_GAP_MS: u64 = 1_000;
+const DEFAULT_STOP_GAP_MS: u64 = 20;
+const DATA_PROFILE_WINDOW_BYTES: usize = 4_096;
+const DATA_PROFILE_MIN_SAMPLE: usize = 64;
+const DATA_PROFILE_TEXT_RATIO: f64 = 0.9;
+// Single channel used instead of the granular event names when unified_events is on.
+const UNIFIED_EVENT: &str = "daemon_event";
 const OPENAI_RESPONSES_URL: &str = "https://api.openai.com/v1/responses";
+const REDACTED: &str = "[redacted]";
+const DIAGNOSTICS_LOG_TAIL_LINES: usize = 2000;
+const MAX_SERIAL_CAPTURE_MS: u64 = 60_000;
+const DEFAULT_SERIAL_BAUD: u32 = 115_200;
+const DEFAULT_SERIAL_READ_TIMEOUT_MS: u64 = 120;
+// The reader only notices a disconnect between reads, so keep this bounded.
+const MAX_SERIAL_READ_TIMEOUT_MS: u64 = 5_000;
+const DEFAULT_BAUD_NEGOTIATION_TIMEOUT_MS: u64 = 2_000;
+const DEFAULT_DNS_RESOLVE_TIMEOUT_MS: u64 = 5_000;
+const OPERATION_CANCELLED: &str = "CANCELLED";
+const MOVEMENT_FROZEN: &str = "FROZEN";
+const MOVEMENT_FREEZE_EVENT: &str = "movement_freeze";
+const SERIAL_INJECT_ENV: &str = "DAEMON_ENABLE_SERIAL_INJECT";
+const SERIAL_STDOUT_ENV: &str = "DAEMON_SERIAL_STDOUT";
+// udev applies the dialout group a moment after the node appears on plug-in.
+const SERIAL_OPEN_EACCES_RETRIES: u32 = 4;
+const SERIAL_OPEN_EACCES_RETRY_MS: u64 = 100;
+const XMODEM_SOH: u8 = 0x01;
+const XMODEM_STX: u8 = 0x02;
+const XMODEM_EOT: u8 = 0x04;
+const XMODEM_ACK: u8 = 0x06;
+const XMODEM_NAK: u8 = 0x15;
+const XMODEM_CAN: u8 = 0x18;
+const XMODEM_CRC_REQUEST: u8 = b'C';
+const XMODEM_PAD: u8 = 0x1a;
+const XMODEM_MAX_RETRIES: u32 = 10;
+const XMODEM_START_TIMEOUT_MS: u64 = 60_000;
+const XMODEM_ACK_TIMEOUT_MS: u64 = 10_000;
+const CANCEL_POLL_MS: u64 = 100;
+// Slack on top of the read timeout while waiting for the reader to park.
+const READER_PAUSE_ACK_MARGIN_MS: u64 = 1_000;
+// A TCP tap client that can't take a chunk within this long is dropped so it can't stall the others.
+const SERIAL_TCP_TAP_WRITE_TIMEOUT_MS: u64 = 200;
+const DEVICE_PROFILES_FILE: &str = "device_profiles.json";
+const CONNECTION_PROFILES_FILE: &str = "connection_profiles.json";
+const CONFIG_SNAPSHOT_VERSION: u32 = 1;
+const DEFAULT_SERIAL_ALIAS_PREFIX: &str = "!";
+const SHUTDOWN_PROFILE_NAME: &str = "last_session";
+const SHUTDOWN_STOP_ACK_TIMEOUT_MS: u64 = 1_000;
+const COMMAND_LOG_FILE: &str = "command_log.sqlite3";
+const COMMAND_LOG_DEFAULT_LIMIT: u32 = 500;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -47,13 +119,130 @@ struct CriticStepResult {
     raw: Value,
 }
 
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MovementFreezeEvent {
+    frozen: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialReadyEvent {
+    port_name: String,
+    reason: String,
+}
+
+// SERIAL_EVENT payload for sessions opened with debug_raw; otherwise the line is sent as a bare string.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialLineEvent {
+    line: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_hex: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CapturedSerialLine {
+    ts_ms: u128,
+    line: String,
+}
+
+// Extra consumers of reader-thread lines (captures, waits). Senders whose receiver
+// has been dropped are pruned on the next line.
+type SerialLineTaps = Arc<Mutex<Vec<mpsc::Sender<CapturedSerialLine>>>>;
+// Same idea for raw received chunks, before line splitting.
+type SerialByteTaps = Arc<Mutex<Vec<mpsc::Sender<Vec<u8>>>>>;
+
+// Pause requests are counted so overlapping callers don't un-pause each other, and the reader
+// reports when it is parked so callers know no read is still in flight.
+#[derive(Default)]
+struct ReaderPause {
+    requests: AtomicU32,
+    parked: Mutex<bool>,
+    parked_changed: Condvar,
+}
+
+impl ReaderPause {
+    // Reader side: called before every read. Returns true while the reader must stay parked.
+    fn park_if_requested(&self) -> bool {
+        let Ok(mut parked) = self.parked.lock() else {
+            return false;
+        };
+        let requested = self.requests.load(Ordering::SeqCst) > 0;
+        if requested && !*parked {
+            self.parked_changed.notify_all();
+        }
+        *parked = requested;
+        requested
+    }
+
+    // Reader side: the thread is exiting and will never read again.
+    fn park_forever(&self) {
+        if let Ok(mut parked) = self.parked.lock() {
+            *parked = true;
+            self.parked_changed.notify_all();
+        }
+    }
+}
+
+// Releases one pause request when dropped.
+struct ReaderPauseGuard {
+    pause: Arc<ReaderPause>,
+}
+
+impl Drop for ReaderPauseGuard {
+    fn drop(&mut self) {
+        self.pause.requests.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 #[derive(Clone)]
 struct SerialSession {
     writer: Arc<Mutex<Box<dyn SerialPort + Send>>>,
     stop_tx: mpsc::Sender<()>,
     port_name: String,
+    baud_rate: Arc<AtomicU32>,
+    line_taps: SerialLineTaps,
+    byte_taps: SerialByteTaps,
+    closed: Arc<AtomicBool>,
+    delimiter: Arc<AtomicU8>,
+    read_timeout_ms: Arc<AtomicU64>,
+    // While paused, the reader stops reading and drops any partial line; see pause_serial_reader.
+    reader_pause: Arc<ReaderPause>,
+    // False during the post-connect grace period; see connect_grace_ms.
+    writes_ready: Arc<AtomicBool>,
+    data_profile: Arc<Mutex<ByteClassWindow>>,
+    // Epoch ms of the last successful open; auto-reconnect resets it in place.
+    connected_at_ms: Arc<AtomicU64>,
+    parity_error_policy: String,
+    // Kept so rebind_serial can reopen on another path with the same settings.
+    connect_options: SerialConnectOptions,
+}
+
+// Printable/non-printable tally over the last DATA_PROFILE_WINDOW_BYTES received.
+#[derive(Default)]
+struct ByteClassWindow {
+    recent: VecDeque<bool>,
+    printable: usize,
+}
+
+impl ByteClassWindow {
+    fn record(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            let printable = matches!(byte, 0x20..=0x7e | b'\t' | b'\r' | b'\n');
+            self.recent.push_back(printable);
+            if printable {
+                self.printable += 1;
+            }
+            if self.recent.len() > DATA_PROFILE_WINDOW_BYTES && self.recent.pop_front() == Some(true) {
+                self.printable -= 1;
+            }
+        }
+    }
 }
 
+
 #[derive(Clone)]
 struct NodeManifestSummary {
     raw: Value,
@@ -68,11 +257,74 @@ struct OrchestratorProcess {
     http_base_url: String,
 }
 
+// Settings and caches added here must also be cleared in reset_tunable_state.
 #[derive(Default)]
 struct AppState {
     session: Mutex<Option<SerialSession>>,
     orchestrator_proc: Mutex<Option<OrchestratorProcess>>,
     critic_session: Mutex<Option<CriticSession>>,
+    operations: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    serial_pattern_stop: Mutex<Option<Arc<AtomicBool>>>,
+    last_connect_failure: Mutex<Option<ConnectDiagnostics>>,
+    command_log: Mutex<Option<rusqlite::Connection>>,
+    serial_tcp_tap: Mutex<Option<SerialTcpTap>>,
+    // Headless use: also print every serial line to stdout.
+    stdout_mirror: AtomicBool,
+    // Overrides SERIAL_EVENT for hosts that already use that name.
+    serial_event_name: Mutex<Option<String>>,
+    // Copy of the session metadata for get_connection_status, so polling never
+    // touches the session slot. Republished whenever the session or its baud changes.
+    connection_status: RwLock<ConnectionStatus>,
+    unified_events: AtomicBool,
+    port_watch: Mutex<Option<PortWatch>>,
+    // Soft freeze: movement dispatch is refused but connections stay up.
+    movement_frozen: AtomicBool,
+    // Most recent backend events, so a reloaded UI can see what it missed.
+    event_log: Mutex<VecDeque<BackendEventRecord>>,
+    // send_serial_line shortcuts: "<prefix><name>" expands to the stored lines.
+    serial_aliases: Mutex<BTreeMap<String, Vec<String>>>,
+    serial_alias_prefix: Mutex<Option<String>>,
+}
+
+struct SerialTcpTap {
+    stop: Arc<AtomicBool>,
+    accept_thread: thread::JoinHandle<()>,
+}
+
+// Hotplug poller shared by every subscribe_port_changes caller; stops with the last one.
+struct PortWatch {
+    stop: Arc<AtomicBool>,
+    subscribers: u32,
+}
+
+// Registration for a cancellable long-running command. Dropping it removes the
+// operation id from the registry.
+struct OperationGuard<'a> {
+    registry: &'a Mutex<HashMap<String, Arc<AtomicBool>>>,
+    id: Option<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl OperationGuard<'_> {
+    fn check(&self) -> Result<(), String> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            return Err(format!(
+                "{OPERATION_CANCELLED} {}",
+                self.id.as_deref().unwrap_or("operation")
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = &self.id {
+            if let Ok(mut ops) = self.registry.lock() {
+                ops.remove(id);
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -86,18 +338,358 @@ struct CriticSession {
     reward_threshold: f64,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct SerialPortEntry {
     port_name: String,
     port_type: String,
+    // USB descriptor fields; None for non-USB ports.
+    vid: Option<u16>,
+    pid: Option<u16>,
+    serial_number: Option<String>,
+    manufacturer: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ConnectionStatus {
     connected: bool,
     port_name: Option<String>,
+    baud_rate: Option<u32>,
+    // What the adapter reports after configuring; some silently snap to the nearest rate.
+    actual_baud: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialBaudMismatchEvent {
+    port_name: String,
+    requested: u32,
+    actual: u32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct XmodemProgressEvent {
+    block: u32,
+    total_blocks: u32,
+    bytes_sent: u64,
+    total_bytes: u64,
+    retries: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct XmodemTransferResult {
+    path: String,
+    total_bytes: u64,
+    blocks: u32,
+    block_size: usize,
+    crc_mode: bool,
+    retries: u32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialReconnectEvent {
+    port_name: String,
+    attempt: u32,
+    delay_ms: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialRepeatEvent {
+    port_name: String,
+    line: String,
+    count: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialProgressEvent {
+    port_name: String,
+    text: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendEventRecord {
+    ts_ms: u128,
+    event_type: String,
+    payload: Value,
+}
+
+#[derive(Clone, Serialize)]
+struct UnifiedEvent<'a, S> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    payload: S,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CodeVerifyResult {
+    matched: bool,
+    expected_line_count: usize,
+    received_line_count: usize,
+    matched_lines: Vec<usize>,
+    // 1-based line numbers that differ, including lines present on only one side.
+    mismatched_lines: Vec<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialBenchmarkResult {
+    payload_size: usize,
+    iterations: u32,
+    completed: u32,
+    // Echoes that arrived complete but with different bytes.
+    mismatched: u32,
+    timed_out: u32,
+    bytes_per_sec: f64,
+    latency_p50_ms: Option<f64>,
+    latency_p90_ms: Option<f64>,
+    latency_p99_ms: Option<f64>,
+    latency_max_ms: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialDrainResult {
+    bytes_discarded: u64,
+    elapsed_ms: u64,
+    // False when MAX_SERIAL_CAPTURE_MS ran out before the device went quiet.
+    quiet: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialDataProfile {
+    sample_bytes: usize,
+    printable_ratio: Option<f64>,
+    // "text", "binary", or "unknown" until DATA_PROFILE_MIN_SAMPLE bytes have arrived.
+    verdict: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialRefreshResult {
+    ports: Vec<SerialPortEntry>,
+    // Port of a session that was dropped because its device no longer enumerates.
+    stale_session_closed: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMecanumResult {
+    bytes_sent: usize,
+    // None when no stop_byte was requested, so no stop was written.
+    stop_repeat: Option<u32>,
+    stops_sent: u32,
+    stops_failed: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialParityPolicyStatus {
+    port_name: String,
+    policy: String,
+    supported_policies: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialUptime {
+    port_name: String,
+    connected_at_ms: u128,
+    uptime_secs: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialBufferStatus {
+    port_name: String,
+    bytes_to_read: u32,
+    bytes_to_write: u32,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SerialDriverInfo {
+    port_name: String,
+    platform: String,
+    driver: Option<String>,
+    usb_path: Option<String>,
+    sysfs_path: Option<String>,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    serial_number: Option<String>,
+    vid: Option<u16>,
+    pid: Option<u16>,
+}
+
+// Error body for connect_serial/send_serial_line. `kind` is the std::io::ErrorKind name
+// (e.g. "BrokenPipe", "TimedOut") when the failure came from the OS, so callers need not parse text.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialCommandError {
+    message: String,
+    kind: Option<String>,
+}
+
+impl From<String> for SerialCommandError {
+    fn from(message: String) -> Self {
+        Self { message, kind: None }
+    }
+}
+
+fn serial_error_kind_name(kind: serialport::ErrorKind) -> String {
+    match kind {
+        serialport::ErrorKind::Io(io_kind) => format!("{io_kind:?}"),
+        other => format!("{other:?}"),
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectDiagnostics {
+    ts_ms: u128,
+    target: String,
+    raw_error: String,
+    category: String,
+    remediation: String,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PortDiagnosis {
+    port_name: String,
+    enumerated: bool,
+    port_type: Option<String>,
+    held_by_this_app: bool,
+    // None when the open test was skipped because this app already holds the port.
+    openable: Option<bool>,
+    open_error: Option<ConnectDiagnostics>,
+    device_node_exists: Option<bool>,
+    mode: Option<String>,
+    owner_uid: Option<u32>,
+    owner_gid: Option<u32>,
+    // The single most likely reason a connect would fail, or "ok".
+    verdict: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandLogStatus {
+    enabled: bool,
+    path: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CommandLogFilter {
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    transport: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandLogEntry {
+    id: i64,
+    ts_ms: i64,
+    transport: String,
+    event: String,
+    detail: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialTcpTapStatus {
+    running: bool,
+    bind_addr: Option<String>,
+    allow_writes: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerialPatternStatus {
+    running: bool,
+    pattern: Option<String>,
+    interval_ms: Option<u64>,
+    repeat_count: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaudProbeResult {
+    requested: u32,
+    actual: Option<u32>,
+    supported: bool,
+    error: Option<String>,
+}
+
+// Optional connect_serial behaviour; every field defaults to the plain connect.
+#[derive(Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SerialConnectOptions {
+    // Look up the baud saved for this device's USB serial number.
+    auto_baud: bool,
+    // Send each SERIAL_EVENT line as { line, rawHex } with the bytes it was decoded from.
+    debug_raw: bool,
+    // Reject writes for this long after opening (devices that reboot on connect).
+    connect_grace_ms: u64,
+    // Lift the grace period early once a line containing this text arrives.
+    ready_banner: Option<String>,
+    // Treat a bare \r as "overwrite the progress line" and emit SERIAL_PROGRESS_EVENT.
+    cr_progress: bool,
+    // Collapse consecutive identical lines into a SERIAL_REPEAT_EVENT at most once per window; 0 = off.
+    repeat_window_ms: u64,
+    // Reopen the same port with backoff when the reader hits an I/O error.
+    auto_reconnect: bool,
+    // Give up after this many reopen attempts; 0 = keep trying until disconnect_serial.
+    max_reconnect_attempts: u32,
+    // "ignore", "mark" or "replace"; see serial_parity_policy_supported.
+    parity_error_policy: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceSerialProfile {
+    serial_number: String,
+    label: Option<String>,
+    baud_rate: u32,
+}
+
+// A named, switchable connection. Only the serial transport exists in this build;
+// the field is kept so stored profiles stay readable if more transports are added.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NamedConnectionProfile {
+    name: String,
+    transport: String,
+    port_name: String,
+    baud_rate: Option<u32>,
+    #[serde(default)]
+    options: SerialConnectOptions,
+}
+
+// Everything user-tunable in this backend, for export_config / import_config.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigSnapshot {
+    version: u32,
+    serial_event_name: Option<String>,
+    unified_events: bool,
+    stdout_mirror: bool,
+    device_profiles: BTreeMap<String, DeviceSerialProfile>,
+    connection_profiles: BTreeMap<String, NamedConnectionProfile>,
+    #[serde(default)]
+    serial_aliases: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    serial_alias_prefix: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -109,6 +701,35 @@ struct OrchestratorProcessStatus {
     args: Option<Vec<String>>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShutdownSummary {
+    stop_sent: bool,
+    stop_acknowledged: Option<bool>,
+    stop_error: Option<String>,
+    profile_saved: Option<String>,
+    profile_error: Option<String>,
+    serial_disconnected: bool,
+    orchestrator_stopped: bool,
+    command_log_closed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackendResetStatus {
+    serial_disconnected: bool,
+    orchestrator_stopped: bool,
+    critic_cleared: bool,
+    command_log_closed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsExportStatus {
+    path: String,
+    entries: Vec<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct NodeProbeStatus {
@@ -137,52 +758,518 @@ fn port_type_name(port_type: &serialport::SerialPortType) -> String {
     }
 }
 
-fn emit_serial_line(app: &AppHandle, line: String) {
-    let _ = app.emit(SERIAL_EVENT, line);
+fn serial_port_entry(port: serialport::SerialPortInfo) -> SerialPortEntry {
+    let port_type = port_type_name(&port.port_type);
+    let usb = match port.port_type {
+        serialport::SerialPortType::UsbPort(info) => Some(info),
+        _ => None,
+    };
+    SerialPortEntry {
+        port_name: port.port_name,
+        port_type,
+        vid: usb.as_ref().map(|info| info.vid),
+        pid: usb.as_ref().map(|info| info.pid),
+        serial_number: usb.as_ref().and_then(|info| info.serial_number.clone()),
+        manufacturer: usb.and_then(|info| info.manufacturer),
+    }
+}
+
+fn usb_port_name_for_serial_number(serial_number: &str) -> Option<String> {
+    serialport::available_ports()
+        .ok()?
+        .into_iter()
+        .find(|port| {
+            matches!(&port.port_type, serialport::SerialPortType::UsbPort(info)
+                if info.serial_number.as_deref() == Some(serial_number))
+        })
+        .map(|port| port.port_name)
 }
 
-fn stop_session_locked(slot: &mut Option<SerialSession>) {
-    if let Some(session) = slot.take() {
-        let _ = session.stop_tx.send(());
+fn usb_serial_number_for_port(port_name: &str) -> Option<String> {
+    serialport::available_ports()
+        .ok()?
+        .into_iter()
+        .find(|port| port.port_name == port_name)
+        .and_then(|port| match port.port_type {
+            serialport::SerialPortType::UsbPort(info) => info.serial_number,
+            _ => None,
+        })
+}
+
+fn diagnose_serial_open_error(port_name: &str, error: &serialport::Error, raw_error: String) -> ConnectDiagnostics {
+    let (category, remediation) = match error.kind() {
+        serialport::ErrorKind::NoDevice => (
+            "busy",
+            "The port is missing or held by another program. Close other serial monitors (Arduino IDE, screen) and check the cable.",
+        ),
+        serialport::ErrorKind::InvalidInput => (
+            "invalid_config",
+            "The port rejected the requested settings. Verify the port name and choose a baud rate the adapter supports.",
+        ),
+        serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => (
+            "permission_denied",
+            "The OS denied access to the port. On Linux add your user to the dialout group (sudo usermod -aG dialout $USER) and log in again.",
+        ),
+        serialport::ErrorKind::Io(std::io::ErrorKind::NotFound) => (
+            "not_found",
+            "The port does not exist. Check the cable, then refresh the port list and pick the device again.",
+        ),
+        serialport::ErrorKind::Io(std::io::ErrorKind::TimedOut) => (
+            "timeout",
+            "The device did not respond while opening. Unplug and replug the adapter, then retry.",
+        ),
+        _ => (
+            "unknown",
+            "Unplug and replug the device, then retry. If it persists, include this error in a diagnostics export.",
+        ),
+    };
+    ConnectDiagnostics {
+        ts_ms: unix_ts_ms(),
+        target: port_name.to_string(),
+        raw_error,
+        category: category.to_string(),
+        remediation: remediation.to_string(),
     }
 }
 
-fn stop_orchestrator_locked(slot: &mut Option<OrchestratorProcess>) {
-    if let Some(mut proc_) = slot.take() {
-        // Best-effort terminate. If this fails, we still drop the handle.
-        let _ = proc_.child.kill();
-        let _ = proc_.child.wait();
+fn record_command_log(state: &AppState, transport: &str, event: &str, detail: Value) {
+    // Best-effort: logging must never fail the command it describes.
+    let Ok(lock) = state.command_log.lock() else {
+        return;
+    };
+    if let Some(conn) = &*lock {
+        let _ = conn.execute(
+            "INSERT INTO command_log (ts_ms, transport, event, detail) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![unix_ts_ms() as i64, transport, event, detail.to_string()],
+        );
     }
+    drop(lock);
+    push_event_log(state, &format!("{transport}.{event}"), detail);
 }
 
-fn normalize_base_url(raw: &str) -> Result<String, String> {
-    let trimmed = raw.trim().trim_end_matches('/');
-    if trimmed.is_empty() {
-        return Err("base_url is empty".to_string());
+fn push_event_log(state: &AppState, event_type: &str, payload: Value) {
+    let Ok(mut log) = state.event_log.lock() else {
+        return;
+    };
+    if log.len() >= EVENT_LOG_CAPACITY {
+        log.pop_front();
     }
-    Ok(trimmed.to_string())
+    log.push_back(BackendEventRecord {
+        ts_ms: unix_ts_ms(),
+        event_type: event_type.to_string(),
+        payload,
+    });
 }
 
-fn normalize_local_host(raw: &str) -> Result<IpAddr, String> {
-    let trimmed = raw.trim();
-    let normalized = if trimmed.eq_ignore_ascii_case("localhost") {
-        "127.0.0.1"
-    } else {
-        trimmed
+#[cfg(target_os = "linux")]
+fn fill_linux_driver_info(info: &mut SerialDriverInfo) {
+    // /dev/ttyUSB0 -> /sys/class/tty/ttyUSB0/device; the driver symlink names the kernel module
+    // and the nearest ancestor with idVendor is the USB device node (e.g. 1-1.2).
+    let Some(tty_name) = Path::new(&info.port_name).file_name().and_then(|n| n.to_str()) else {
+        return;
     };
-    normalized
-        .parse::<IpAddr>()
-        .map_err(|_| format!("http_host must be an IP address (e.g. 127.0.0.1), got: {trimmed}"))
+    let device_link = Path::new("/sys/class/tty").join(tty_name).join("device");
+    let Ok(device_path) = std::fs::canonicalize(&device_link) else {
+        return;
+    };
+    info.sysfs_path = Some(device_path.display().to_string());
+    info.driver = std::fs::read_link(device_path.join("driver"))
+        .ok()
+        .and_then(|p| p.file_name().and_then(|n| n.to_str()).map(|s| s.to_string()));
+
+    let read_attr = |dir: &Path, name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let mut cur: Option<&Path> = Some(device_path.as_path());
+    while let Some(dir) = cur {
+        if dir.join("idVendor").exists() {
+            info.usb_path = dir.file_name().and_then(|n| n.to_str()).map(|s| s.to_string());
+            info.manufacturer = info.manufacturer.take().or_else(|| read_attr(dir, "manufacturer"));
+            info.product = info.product.take().or_else(|| read_attr(dir, "product"));
+            info.serial_number = info.serial_number.take().or_else(|| read_attr(dir, "serial"));
+            break;
+        }
+        cur = dir.parent();
+    }
 }
 
-fn pick_free_tcp_port(host: IpAddr, preferred: u16) -> Result<u16, String> {
-    let preferred_addr = SocketAddr::new(host, preferred);
-    if let Ok(listener) = TcpListener::bind(preferred_addr) {
-        drop(listener);
-        return Ok(preferred);
+fn app_config_file(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to resolve app config directory: {e}"))?;
+    Ok(dir.join(file_name))
+}
+
+fn load_config_map<T: DeserializeOwned>(app: &AppHandle, file_name: &str) -> Result<BTreeMap<String, T>, String> {
+    let path = app_config_file(app, file_name)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
     }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid profiles in {}: {e}", path.display()))
+}
 
-    let listener = TcpListener::bind(SocketAddr::new(host, 0))
+fn store_config_map<T: Serialize>(app: &AppHandle, file_name: &str, map: &BTreeMap<String, T>) -> Result<(), String> {
+    let path = app_config_file(app, file_name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let content = serde_json::to_string_pretty(map)
+        .map_err(|e| format!("Failed to serialize {file_name}: {e}"))?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+fn load_device_profiles(app: &AppHandle) -> Result<BTreeMap<String, DeviceSerialProfile>, String> {
+    load_config_map(app, DEVICE_PROFILES_FILE)
+}
+
+fn store_device_profiles(app: &AppHandle, profiles: &BTreeMap<String, DeviceSerialProfile>) -> Result<(), String> {
+    store_config_map(app, DEVICE_PROFILES_FILE, profiles)
+}
+
+// Flips writes_ready exactly once and announces it, whichever of the grace timer
+// or the banner match gets there first.
+fn mark_serial_ready(app: &AppHandle, writes_ready: &AtomicBool, port_name: &str, reason: &str) {
+    if writes_ready
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_ok()
+    {
+        emit_backend_event(
+            app,
+            SERIAL_READY_EVENT,
+            SerialReadyEvent {
+                port_name: port_name.to_string(),
+                reason: reason.to_string(),
+            },
+        );
+    }
+}
+
+// A \r that isn't the first half of \r\n. A trailing \r is left until the next byte shows which it is.
+fn bare_cr_position(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|w| w[0] == b'\r' && w[1] != b'\n')
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Every backend event goes through here so unified_events can fold them onto one channel.
+// Per-line and per-block streams stay out of the event log; they'd evict everything else.
+fn emit_backend_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if !matches!(event, SERIAL_PROGRESS_EVENT | XMODEM_PROGRESS_EVENT) {
+        if let (Some(state), Ok(value)) = (app.try_state::<AppState>(), serde_json::to_value(&payload)) {
+            push_event_log(&state, event, value);
+        }
+    }
+    dispatch_event(app, event, payload);
+}
+
+fn dispatch_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    let unified = app
+        .try_state::<AppState>()
+        .is_some_and(|state| state.unified_events.load(Ordering::Relaxed));
+    if unified {
+        let _ = app.emit(UNIFIED_EVENT, UnifiedEvent { kind: event, payload });
+    } else {
+        let _ = app.emit(event, payload);
+    }
+}
+
+fn emit_serial_line(app: &AppHandle, line: String, raw_hex: Option<String>) {
+    let mut event_name = SERIAL_EVENT.to_string();
+    if let Some(state) = app.try_state::<AppState>() {
+        if state.stdout_mirror.load(Ordering::Relaxed) {
+            let mut out = std::io::stdout().lock();
+            let _ = writeln!(out, "{line}");
+            let _ = out.flush();
+        }
+        if let Some(name) = state.serial_event_name.lock().ok().and_then(|lock| lock.clone()) {
+            event_name = name;
+        }
+        // Device output has its own scrollback; only backend-reported errors are kept.
+        if line.starts_with("ERR ") {
+            push_event_log(&state, &event_name, Value::String(line.clone()));
+        }
+    }
+    match raw_hex {
+        Some(raw_hex) => dispatch_event(
+            app,
+            &event_name,
+            SerialLineEvent {
+                line,
+                raw_hex: Some(raw_hex),
+            },
+        ),
+        None => dispatch_event(app, &event_name, line),
+    }
+}
+
+fn forward_to_line_taps(taps: &SerialLineTaps, line: &str) {
+    let Ok(mut taps) = taps.lock() else {
+        return;
+    };
+    if taps.is_empty() {
+        return;
+    }
+    let captured = CapturedSerialLine {
+        ts_ms: unix_ts_ms(),
+        line: line.to_string(),
+    };
+    taps.retain(|tx| tx.send(captured.clone()).is_ok());
+}
+
+fn active_serial_session(state: &AppState) -> Result<SerialSession, String> {
+    let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+    lock.clone().ok_or_else(|| "No active serial connection".to_string())
+}
+
+fn register_line_tap(session: &SerialSession) -> Result<mpsc::Receiver<CapturedSerialLine>, String> {
+    let (tx, rx) = mpsc::channel::<CapturedSerialLine>();
+    session
+        .line_taps
+        .lock()
+        .map_err(|_| "Serial tap lock poisoned".to_string())?
+        .push(tx);
+    Ok(rx)
+}
+
+fn write_session_bytes(session: &SerialSession, bytes: &[u8]) -> Result<(), String> {
+    try_write_session_bytes(session, bytes).map_err(|error| error.message)
+}
+
+fn try_write_session_bytes(session: &SerialSession, bytes: &[u8]) -> Result<(), SerialCommandError> {
+    if !session.writes_ready.load(Ordering::SeqCst) {
+        return Err("Device warming up: writes are blocked until serial_ready".to_string().into());
+    }
+    let mut writer = session
+        .writer
+        .lock()
+        .map_err(|_| "Serial writer lock poisoned".to_string())?;
+    writer.write_all(bytes).map_err(|error| SerialCommandError {
+        message: format!("Serial write failed: {error}"),
+        kind: Some(format!("{:?}", error.kind())),
+    })?;
+    writer.flush().map_err(|error| SerialCommandError {
+        message: format!("Serial flush failed: {error}"),
+        kind: Some(format!("{:?}", error.kind())),
+    })
+}
+
+// Sends `request_line` and collects reader lines until one contains `end_marker`.
+// The tap is registered before writing so a fast reply can't be missed.
+fn send_and_collect_until(
+    session: &SerialSession,
+    operation: &OperationGuard<'_>,
+    request_line: &str,
+    end_marker: &str,
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    let rx = register_line_tap(session)?;
+    write_session_bytes(session, format!("{}\n", request_line.trim()).as_bytes())?;
+    collect_until_marker(&rx, operation, end_marker, timeout)
+}
+
+// Gathers tapped lines until one contains end_marker; the request must already have been sent.
+fn collect_until_marker(
+    rx: &mpsc::Receiver<CapturedSerialLine>,
+    operation: &OperationGuard<'_>,
+    end_marker: &str,
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    let deadline = std::time::Instant::now() + timeout;
+    let mut lines: Vec<String> = Vec::new();
+    loop {
+        operation.check()?;
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return Err(format!(
+                "Timed out after {}ms waiting for {end_marker} ({} lines received)",
+                timeout.as_millis(),
+                lines.len()
+            ));
+        }
+        match rx.recv_timeout(remaining.min(Duration::from_millis(CANCEL_POLL_MS))) {
+            Ok(captured) => {
+                if captured.line.contains(end_marker) {
+                    return Ok(lines);
+                }
+                lines.push(captured.line);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("Serial session closed while waiting for response".to_string());
+            }
+        }
+    }
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn xmodem_block_frame(block_num: u8, chunk: &[u8], block_size: usize, crc_mode: bool) -> Vec<u8> {
+    let mut data = chunk.to_vec();
+    data.resize(block_size, XMODEM_PAD);
+    let mut frame = Vec::with_capacity(block_size + 5);
+    frame.push(if block_size == 1024 { XMODEM_STX } else { XMODEM_SOH });
+    frame.push(block_num);
+    frame.push(255 - block_num);
+    frame.extend_from_slice(&data);
+    if crc_mode {
+        frame.extend_from_slice(&crc16_xmodem(&data).to_be_bytes());
+    } else {
+        frame.push(data.iter().fold(0_u8, |acc, b| acc.wrapping_add(*b)));
+    }
+    frame
+}
+
+// Next byte from the receiver, buffering whole tap chunks. Ok(None) means timeout.
+fn xmodem_read_byte(
+    rx: &mpsc::Receiver<Vec<u8>>,
+    pending: &mut VecDeque<u8>,
+    operation: &OperationGuard<'_>,
+    timeout: Duration,
+) -> Result<Option<u8>, String> {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        if let Some(byte) = pending.pop_front() {
+            return Ok(Some(byte));
+        }
+        operation.check()?;
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        match rx.recv_timeout(remaining.min(Duration::from_millis(CANCEL_POLL_MS))) {
+            Ok(chunk) => pending.extend(chunk),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("Serial session closed during XMODEM transfer".to_string());
+            }
+        }
+    }
+}
+
+fn forward_to_byte_taps(taps: &SerialByteTaps, bytes: &[u8]) {
+    let Ok(mut taps) = taps.lock() else {
+        return;
+    };
+    if !taps.is_empty() {
+        taps.retain(|tx| tx.send(bytes.to_vec()).is_ok());
+    }
+}
+
+fn register_byte_tap(session: &SerialSession) -> Result<mpsc::Receiver<Vec<u8>>, String> {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    session
+        .byte_taps
+        .lock()
+        .map_err(|_| "Serial tap lock poisoned".to_string())?
+        .push(tx);
+    Ok(rx)
+}
+
+// The accept thread owns the listener, so joining it guarantees the address is free again.
+fn stop_serial_tcp_tap_locked(slot: &mut Option<SerialTcpTap>) {
+    if let Some(tap) = slot.take() {
+        tap.stop.store(true, Ordering::SeqCst);
+        let _ = tap.accept_thread.join();
+    }
+}
+
+fn begin_operation(state: &AppState, operation_id: Option<String>) -> Result<OperationGuard<'_>, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let id = operation_id.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(id) = &id {
+        let mut ops = state.operations.lock().map_err(|_| "State lock poisoned".to_string())?;
+        if ops.contains_key(id) {
+            return Err(format!("operation_id {id} is already in use"));
+        }
+        ops.insert(id.clone(), cancelled.clone());
+    }
+    Ok(OperationGuard {
+        registry: &state.operations,
+        id,
+        cancelled,
+    })
+}
+
+fn publish_connection_status(state: &AppState, status: ConnectionStatus) {
+    if let Ok(mut snapshot) = state.connection_status.write() {
+        *snapshot = status;
+    }
+}
+
+fn stop_session_locked(slot: &mut Option<SerialSession>) {
+    if let Some(session) = slot.take() {
+        session.closed.store(true, Ordering::SeqCst);
+        let _ = session.stop_tx.send(());
+    }
+}
+
+fn stop_serial_pattern_locked(slot: &mut Option<Arc<AtomicBool>>) {
+    if let Some(stop) = slot.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+}
+
+fn stop_orchestrator_locked(slot: &mut Option<OrchestratorProcess>) {
+    if let Some(mut proc_) = slot.take() {
+        // Best-effort terminate. If this fails, we still drop the handle.
+        let _ = proc_.child.kill();
+        let _ = proc_.child.wait();
+    }
+}
+
+fn normalize_base_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return Err("base_url is empty".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+fn normalize_local_host(raw: &str) -> Result<IpAddr, String> {
+    let trimmed = raw.trim();
+    let normalized = if trimmed.eq_ignore_ascii_case("localhost") {
+        "127.0.0.1"
+    } else {
+        trimmed
+    };
+    normalized
+        .parse::<IpAddr>()
+        .map_err(|_| format!("http_host must be an IP address (e.g. 127.0.0.1), got: {trimmed}"))
+}
+
+fn pick_free_tcp_port(host: IpAddr, preferred: u16) -> Result<u16, String> {
+    let preferred_addr = SocketAddr::new(host, preferred);
+    if let Ok(listener) = TcpListener::bind(preferred_addr) {
+        drop(listener);
+        return Ok(preferred);
+    }
+
+    let listener = TcpListener::bind(SocketAddr::new(host, 0))
         .map_err(|e| format!("Failed to bind ephemeral port on {host}: {e}"))?;
     let port = listener
         .local_addr()
@@ -213,11 +1300,25 @@ fn wait_for_tcp_listen(host: IpAddr, port: u16, child: &mut Child, timeout: Dura
     }
 }
 
-fn resolve_socket_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
-    let addrs = (host, port)
-        .to_socket_addrs()
-        .map_err(|error| format!("Failed to resolve {host}:{port}: {error}"))?
-        .collect::<Vec<_>>();
+fn resolve_socket_addrs(host: &str, port: u16, timeout: Duration) -> Result<Vec<SocketAddr>, String> {
+    // The system resolver can't be interrupted, so it runs on a worker we stop waiting for.
+    let (tx, rx) = mpsc::channel();
+    let lookup_host = host.to_string();
+    thread::spawn(move || {
+        let result = (lookup_host.as_str(), port)
+            .to_socket_addrs()
+            .map(|addrs| addrs.collect::<Vec<_>>());
+        let _ = tx.send(result);
+    });
+    let addrs = match rx.recv_timeout(timeout) {
+        Ok(result) => result.map_err(|error| format!("Failed to resolve {host}:{port}: {error}"))?,
+        Err(_) => {
+            return Err(format!(
+                "DNS resolution timed out after {}ms for {host}:{port}",
+                timeout.as_millis()
+            ))
+        }
+    };
     if addrs.is_empty() {
         return Err(format!("No addresses found for {host}:{port}"));
     }
@@ -504,13 +1605,13 @@ fn parse_manifest_summary(manifest: &Value) -> NodeManifestSummary {
     }
 }
 
-fn probe_daemon_node(host: &str, port: u16) -> Result<NodeManifestSummary, String> {
+fn probe_daemon_node(host: &str, port: u16, resolve_timeout: Duration) -> Result<NodeManifestSummary, String> {
     let host_trimmed = host.trim();
     if host_trimmed.is_empty() {
         return Err("host cannot be empty".to_string());
     }
 
-    let addrs = resolve_socket_addrs(host_trimmed, port)?;
+    let addrs = resolve_socket_addrs(host_trimmed, port, resolve_timeout)?;
     let mut last_error = None;
 
     for addr in addrs {
@@ -882,148 +1983,2558 @@ fn read_desktop_audit_log(tail_lines: Option<usize>) -> Result<String, String> {
     Ok(lines[start..].join("\n"))
 }
 
+fn is_secret_key(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    ["token", "password", "secret", "api_key", "apikey", "authorization"]
+        .iter()
+        .any(|s| k == *s || k.ends_with(&format!("_{s}")))
+}
+
+fn redact_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    if is_secret_key(k) {
+                        (k.clone(), Value::String(REDACTED.to_string()))
+                    } else {
+                        (k.clone(), redact_secrets(v))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(arr) => Value::Array(arr.iter().map(redact_secrets).collect()),
+        _ => value.clone(),
+    }
+}
+
+fn redact_log_text(text: &str, max_lines: usize) -> String {
+    // Log lines are JSON objects; anything that doesn't parse is kept verbatim.
+    // The OpenAI key is also scrubbed textually in case it leaked into an error string.
+    let api_key = openai_api_key();
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    lines[start..]
+        .iter()
+        .map(|line| {
+            let redacted = match serde_json::from_str::<Value>(line) {
+                Ok(v) => redact_secrets(&v).to_string(),
+                Err(_) => line.to_string(),
+            };
+            match api_key.as_deref() {
+                Some(key) => redacted.replace(key, REDACTED),
+                None => redacted,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn diagnostics_state_dump(state: &AppState) -> Result<Value, String> {
+    let serial = {
+        let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        json!({
+            "connected": lock.is_some(),
+            "port_name": lock.as_ref().map(|s| s.port_name.clone()),
+        })
+    };
+    let orchestrator = {
+        let mut lock = state
+            .orchestrator_proc
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        match &mut *lock {
+            Some(proc_) => json!({
+                "running": matches!(proc_.child.try_wait(), Ok(None)),
+                "pid": proc_.child.id(),
+                "http_base_url": proc_.http_base_url.clone(),
+                "args": proc_.args.clone(),
+            }),
+            None => json!({ "running": false }),
+        }
+    };
+    let critic = {
+        let lock = state
+            .critic_session
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        match &*lock {
+            Some(s) => json!({
+                "running": true,
+                "orchestrator_base_url": s.orchestrator_base_url.clone(),
+                "task": s.task.clone(),
+                "model": s.model.clone(),
+                "success_streak": s.success_streak,
+                "success_n": s.success_n,
+            }),
+            None => json!({ "running": false }),
+        }
+    };
+    Ok(json!({
+        "ts_ms": unix_ts_ms(),
+        "serial": serial,
+        "orchestrator": orchestrator,
+        "critic": critic,
+    }))
+}
+
+#[tauri::command]
+fn export_diagnostics(state: State<'_, AppState>, path: String) -> Result<DiagnosticsExportStatus, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err("path cannot be empty".to_string());
+    }
+
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let state_dump = redact_secrets(&diagnostics_state_dump(&state)?);
+    let state_text = serde_json::to_string_pretty(&state_dump)
+        .map_err(|e| format!("Failed to serialize state dump: {e}"))?;
+    entries.push(("state.json".to_string(), state_text));
+
+    // Command history lives in the JSONL logs (backend audit + UI traces).
+    if let Ok(logs_dir) = repo_logs_dir() {
+        if let Ok(read_dir) = std::fs::read_dir(&logs_dir) {
+            let mut log_paths = read_dir
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("jsonl"))
+                .collect::<Vec<_>>();
+            log_paths.sort();
+            for log_path in log_paths {
+                let Some(name) = log_path.file_name().and_then(|n| n.to_str()).map(|s| s.to_string()) else {
+                    continue;
+                };
+                let content = std::fs::read_to_string(&log_path)
+                    .map_err(|e| format!("Failed to read {}: {e}", log_path.display()))?;
+                entries.push((format!("logs/{name}"), redact_log_text(&content, DIAGNOSTICS_LOG_TAIL_LINES)));
+            }
+        }
+    }
+
+    let file = std::fs::File::create(&target)
+        .map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, body) in &entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {name} to {}: {e}", target.display()))?;
+        zip.write_all(body.as_bytes())
+            .map_err(|e| format!("Failed to write {name} to {}: {e}", target.display()))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize {}: {e}", target.display()))?;
+
+    let names = entries.into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+    append_desktop_audit_log(
+        "diagnostics.export",
+        &json!({ "path": target.display().to_string(), "entries": names.clone() }),
+    );
+    Ok(DiagnosticsExportStatus {
+        path: target.display().to_string(),
+        entries: names,
+    })
+}
+
 #[tauri::command]
 fn list_serial_ports() -> Result<Vec<SerialPortEntry>, String> {
+    current_serial_ports()
+}
+
+fn current_serial_ports() -> Result<Vec<SerialPortEntry>, String> {
     let ports = serialport::available_ports().map_err(|error| error.to_string())?;
-    let result = ports
-        .into_iter()
-        .map(|port| SerialPortEntry {
-            port_name: port.port_name,
-            port_type: port_type_name(&port.port_type),
-        })
-        .collect::<Vec<_>>();
+    let result = ports.into_iter().map(serial_port_entry).collect::<Vec<_>>();
     Ok(result)
 }
 
+fn stop_port_watch_locked(slot: &mut Option<PortWatch>) {
+    if let Some(watch) = slot.take() {
+        watch.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+#[tauri::command]
+fn subscribe_port_changes(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<SerialPortEntry>, String> {
+    let snapshot = current_serial_ports()?;
+    // Every subscriber gets the full list up front, even if the poller was already running.
+    emit_backend_event(&app, SERIAL_PORTS_SNAPSHOT_EVENT, snapshot.clone());
+
+    let mut lock = state.port_watch.lock().map_err(|_| "State lock poisoned".to_string())?;
+    if let Some(watch) = lock.as_mut() {
+        watch.subscribers += 1;
+        return Ok(snapshot);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    *lock = Some(PortWatch {
+        stop: stop.clone(),
+        subscribers: 1,
+    });
+    let mut known = snapshot.clone();
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(PORT_WATCH_INTERVAL_MS));
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(current) = current_serial_ports() else {
+            continue;
+        };
+        for port in current.iter().filter(|p| !known.iter().any(|k| k.port_name == p.port_name)) {
+            emit_backend_event(&app, SERIAL_PORT_ADDED_EVENT, port.clone());
+        }
+        for port in known.iter().filter(|k| !current.iter().any(|p| p.port_name == k.port_name)) {
+            emit_backend_event(&app, SERIAL_PORT_REMOVED_EVENT, port.clone());
+        }
+        known = current;
+    });
+    Ok(snapshot)
+}
+
+// Runs for the life of the app, independent of port-change subscribers, so a sleep/resume
+// cycle always re-validates the serial session.
+fn spawn_resume_watch(app: AppHandle) {
+    thread::spawn(move || loop {
+        let slept_from = std::time::SystemTime::now();
+        thread::sleep(Duration::from_millis(PORT_WATCH_INTERVAL_MS));
+        // Monotonic clocks stop during suspend, so wall-clock drift is the resume signal.
+        let resumed = slept_from
+            .elapsed()
+            .is_ok_and(|gap| gap > Duration::from_millis(PORT_WATCH_INTERVAL_MS + RESUME_DETECT_SLACK_MS));
+        if resumed {
+            if let Some(state) = app.try_state::<AppState>() {
+                let _ = refresh_serial_state(&app, &state);
+            }
+        }
+    });
+}
+
+// by-id symlinks and ptys are never enumerated, so a session only counts as gone when its
+// path matches no enumerated port (after resolving links) and no longer exists on disk.
+fn serial_session_is_stale(port_name: &str, ports: &[SerialPortEntry]) -> bool {
+    let canonical = |name: &str| std::fs::canonicalize(name).unwrap_or_else(|_| PathBuf::from(name));
+    let session_path = canonical(port_name);
+    let enumerated = ports
+        .iter()
+        .any(|p| p.port_name == port_name || canonical(&p.port_name) == session_path);
+    !enumerated && !Path::new(port_name).exists()
+}
+
+// Re-enumerates from scratch and drops any state that points at devices which are gone.
+fn refresh_serial_state(app: &AppHandle, state: &AppState) -> Result<SerialRefreshResult, String> {
+    let ports = current_serial_ports()?;
+    if let Ok(mut lock) = state.last_connect_failure.lock() {
+        *lock = None;
+    }
+    let stale_session_closed = {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        let stale = lock
+            .as_ref()
+            .filter(|s| !s.port_name.starts_with("fd:"))
+            .filter(|s| serial_session_is_stale(&s.port_name, &ports))
+            .map(|s| s.port_name.clone());
+        if stale.is_some() {
+            stop_session_locked(&mut lock);
+            publish_connection_status(state, ConnectionStatus::default());
+        }
+        stale
+    };
+    if let Some(port_name) = &stale_session_closed {
+        record_command_log(state, "serial", "disconnect", json!({ "port_name": port_name, "reason": "stale_after_refresh" }));
+    }
+    emit_backend_event(app, SERIAL_PORTS_SNAPSHOT_EVENT, ports.clone());
+    Ok(SerialRefreshResult {
+        ports,
+        stale_session_closed,
+    })
+}
+
+#[tauri::command]
+fn refresh_serial_subsystem(app: AppHandle, state: State<'_, AppState>) -> Result<SerialRefreshResult, String> {
+    refresh_serial_state(&app, &state)
+}
+
+#[tauri::command]
+fn unsubscribe_port_changes(state: State<'_, AppState>) -> Result<u32, String> {
+    let mut lock = state.port_watch.lock().map_err(|_| "State lock poisoned".to_string())?;
+    let remaining = match lock.as_mut() {
+        Some(watch) => {
+            watch.subscribers = watch.subscribers.saturating_sub(1);
+            watch.subscribers
+        }
+        None => return Ok(0),
+    };
+    if remaining == 0 {
+        stop_port_watch_locked(&mut lock);
+    }
+    Ok(remaining)
+}
+
+// The serialport backend opens ports raw (no INPCK/PARMRK on Unix, no per-byte error status on
+// Windows), so a byte with a parity error arrives like any other byte. Only "ignore" can be honoured.
+fn serial_parity_policy_supported(policy: &str) -> Result<(), String> {
+    match policy {
+        "ignore" => Ok(()),
+        "mark" | "replace" => Err(format!(
+            "Parity error policy '{policy}' is not available on {}: the serial backend does not surface per-byte parity errors",
+            std::env::consts::OS
+        )),
+        other => Err(format!("Unknown parity error policy '{other}'; expected ignore, mark or replace")),
+    }
+}
+
+// Everything in the options that can be rejected, checked before any port is opened or adopted.
+fn validate_connect_options(options: &SerialConnectOptions) -> Result<(), String> {
+    if let Some(policy) = options.parity_error_policy.as_deref() {
+        serial_parity_policy_supported(policy.trim())?;
+    }
+    Ok(())
+}
+
+fn clone_serial_reader(port: &dyn SerialPort) -> Result<Box<dyn SerialPort>, String> {
+    port.try_clone()
+        .map_err(|error| format!("Failed to clone serial reader: {error}"))
+}
+
+fn open_serial_port(port_name: &str, baud: u32) -> serialport::Result<Box<dyn SerialPort>> {
+    let open = || {
+        serialport::new(port_name, baud)
+            .timeout(Duration::from_millis(DEFAULT_SERIAL_READ_TIMEOUT_MS))
+            .open()
+    };
+    let mut result = open();
+    // A fresh Linux device node can be EACCES until udev settles; only a denial that
+    // outlasts the retries is reported as a real permission problem.
+    if cfg!(target_os = "linux") {
+        let mut attempts = 0;
+        while attempts < SERIAL_OPEN_EACCES_RETRIES
+            && matches!(
+                &result,
+                Err(e) if e.kind() == serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied)
+            )
+        {
+            thread::sleep(Duration::from_millis(SERIAL_OPEN_EACCES_RETRY_MS));
+            result = open();
+            attempts += 1;
+        }
+    }
+    result
+}
+
+#[tauri::command]
+fn connect_serial(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    port_name: String,
+    baud_rate: Option<u32>,
+    options: Option<SerialConnectOptions>,
+) -> Result<ConnectionStatus, SerialCommandError> {
+    let options = options.unwrap_or_default();
+    validate_connect_options(&options)?;
+    let baud = if options.auto_baud {
+        // Known devices carry their saved baud; anything else falls back to the default.
+        usb_serial_number_for_port(&port_name)
+            .and_then(|sn| load_device_profiles(&app).ok()?.remove(&sn))
+            .map(|profile| profile.baud_rate)
+            .unwrap_or(DEFAULT_SERIAL_BAUD)
+    } else {
+        baud_rate.unwrap_or(DEFAULT_SERIAL_BAUD)
+    };
+
+    let port = match open_serial_port(&port_name, baud) {
+        Ok(port) => port,
+        Err(error) => {
+            let message = format!("Failed to open serial port {port_name}: {error}");
+            if let Ok(mut lock) = state.last_connect_failure.lock() {
+                *lock = Some(diagnose_serial_open_error(&port_name, &error, message.clone()));
+            }
+            return Err(SerialCommandError {
+                message,
+                kind: Some(serial_error_kind_name(error.kind())),
+            });
+        }
+    };
+    if let Ok(mut lock) = state.last_connect_failure.lock() {
+        *lock = None;
+    }
+
+    let reader = clone_serial_reader(port.as_ref())?;
+    Ok(start_serial_session(&app, &state, port, reader, port_name, baud, options)?)
+}
+
+// Checks that a caller-supplied fd is an open tty and puts it in raw mode, matching what
+// serialport::new(..).open() does for ports it opens itself. Does not take ownership.
+#[cfg(unix)]
+fn prepare_raw_tty_fd(fd: i32) -> Result<(), String> {
+    // SAFETY: isatty/tcgetattr/tcsetattr only inspect or configure the descriptor; an invalid
+    // fd makes them fail with EBADF rather than touching memory we don't own.
+    unsafe {
+        if libc::isatty(fd) != 1 {
+            return Err(format!("fd {fd} is not a terminal: {}", std::io::Error::last_os_error()));
+        }
+        let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+        if libc::tcgetattr(fd, termios.as_mut_ptr()) != 0 {
+            return Err(format!("Failed to read termios on fd {fd}: {}", std::io::Error::last_os_error()));
+        }
+        let mut termios = termios.assume_init();
+        libc::cfmakeraw(&mut termios);
+        termios.c_cflag |= libc::CREAD | libc::CLOCAL;
+        if libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0 {
+            return Err(format!("Failed to set raw mode on fd {fd}: {}", std::io::Error::last_os_error()));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn connect_serial_fd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    fd: i32,
+    baud_rate: Option<u32>,
+    options: Option<SerialConnectOptions>,
+) -> Result<ConnectionStatus, String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+        if fd < 0 {
+            return Err(format!("Invalid file descriptor {fd}"));
+        }
+        let baud = baud_rate.unwrap_or(DEFAULT_SERIAL_BAUD);
+        let options = options.unwrap_or_default();
+        validate_connect_options(&options)?;
+        prepare_raw_tty_fd(fd)?;
+        // SAFETY: prepare_raw_tty_fd confirmed fd is an open tty. Every fallible step (option
+        // checks, baud, timeout, reader clone) runs while we can still hand the fd back via
+        // into_raw_fd, so the caller's descriptor is never closed on failure. Once
+        // start_serial_session is reached the session owns it and closes it on disconnect.
+        // from_raw_fd also takes an exclusive lock (flock + TIOCEXCL), released on the error path.
+        let mut port = unsafe { serialport::TTYPort::from_raw_fd(fd) };
+        let prepared = port
+            .set_baud_rate(baud)
+            .map_err(|e| format!("Failed to set baud {baud} on fd {fd}: {e}"))
+            .and_then(|()| {
+                port.set_timeout(Duration::from_millis(DEFAULT_SERIAL_READ_TIMEOUT_MS))
+                    .map_err(|e| format!("Failed to set timeout on fd {fd}: {e}"))
+            })
+            .and_then(|()| {
+                port.try_clone_native()
+                    .map_err(|e| format!("Failed to clone serial reader for fd {fd}: {e}"))
+            });
+        let reader = match prepared {
+            Ok(reader) => reader,
+            Err(error) => {
+                let _ = port.set_exclusive(false);
+                let _ = port.into_raw_fd();
+                return Err(error);
+            }
+        };
+        start_serial_session(
+            &app,
+            &state,
+            Box::new(port),
+            Box::new(reader),
+            format!("fd:{fd}"),
+            baud,
+            options,
+        )
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (app, state, baud_rate, options);
+        Err(format!("connect_serial_fd is only supported on Unix (fd {fd})"))
+    }
+}
+
+// Shared by every connect path once a port is open: spawns the reader and installs the session.
+fn start_serial_session(
+    app: &AppHandle,
+    state: &AppState,
+    port: Box<dyn SerialPort>,
+    mut reader: Box<dyn SerialPort>,
+    port_name: String,
+    baud: u32,
+    options: SerialConnectOptions,
+) -> Result<ConnectionStatus, String> {
+    let connect_options = options.clone();
+    let debug_raw = options.debug_raw;
+    let cr_progress = options.cr_progress;
+    let repeat_window = Duration::from_millis(options.repeat_window_ms);
+    // fd-backed sessions have no path to reopen.
+    let auto_reconnect = options.auto_reconnect && !port_name.starts_with("fd:");
+    let max_reconnect_attempts = options.max_reconnect_attempts;
+    // Lets auto-reconnect follow the same adapter if it re-enumerates under another name.
+    let reconnect_serial_number = if auto_reconnect {
+        usb_serial_number_for_port(&port_name)
+    } else {
+        None
+    };
+    let parity_error_policy = options
+        .parity_error_policy
+        .map(|p| p.trim().to_string())
+        .unwrap_or_else(|| "ignore".to_string());
+    serial_parity_policy_supported(&parity_error_policy)?;
+    let connect_grace_ms = options.connect_grace_ms;
+    let ready_banner = options.ready_banner.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let actual_baud = port.baud_rate().ok();
+    if let Some(actual) = actual_baud.filter(|actual| *actual != baud) {
+        emit_backend_event(
+            app,
+            SERIAL_BAUD_MISMATCH_EVENT,
+            SerialBaudMismatchEvent {
+                port_name: port_name.clone(),
+                requested: baud,
+                actual,
+            },
+        );
+    }
+
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let writer: Arc<Mutex<Box<dyn SerialPort + Send>>> =
+        Arc::new(Mutex::new(port as Box<dyn SerialPort + Send>));
+    let reader_writer = writer.clone();
+    let baud_rate = Arc::new(AtomicU32::new(baud));
+    let reader_baud = baud_rate.clone();
+
+    let line_taps: SerialLineTaps = Arc::new(Mutex::new(Vec::new()));
+    let reader_taps = line_taps.clone();
+    let byte_taps: SerialByteTaps = Arc::new(Mutex::new(Vec::new()));
+    let reader_byte_taps = byte_taps.clone();
+    let data_profile = Arc::new(Mutex::new(ByteClassWindow::default()));
+    let reader_data_profile = data_profile.clone();
+    let connected_at_ms = Arc::new(AtomicU64::new(unix_ts_ms() as u64));
+    let reader_connected_at_ms = connected_at_ms.clone();
+    let delimiter = Arc::new(AtomicU8::new(b'\n'));
+    let reader_delimiter = delimiter.clone();
+    let read_timeout_ms = Arc::new(AtomicU64::new(DEFAULT_SERIAL_READ_TIMEOUT_MS));
+    let reader_timeout_ms = read_timeout_ms.clone();
+    let reader_pause = Arc::new(ReaderPause::default());
+    let reader_pause_ack = reader_pause.clone();
+    let writes_ready = Arc::new(AtomicBool::new(connect_grace_ms == 0));
+    let reader_writes_ready = writes_ready.clone();
+    let mut reader_port_name = port_name.clone();
+    let app_handle = app.clone();
+    thread::spawn(move || {
+        let mut read_buf = [0_u8; 512];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut applied_timeout_ms = DEFAULT_SERIAL_READ_TIMEOUT_MS;
+        // syslog-style "last message repeated N times" state for repeat_window_ms.
+        let mut last_line: Option<String> = None;
+        let mut repeats: u64 = 0;
+        let mut repeats_since = std::time::Instant::now();
+
+        loop {
+            if stop_rx.try_recv().is_ok() {
+                break;
+            }
+            if reader_pause_ack.park_if_requested() {
+                pending.clear();
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            if repeats > 0 && repeats_since.elapsed() >= repeat_window {
+                emit_backend_event(
+                    &app_handle,
+                    SERIAL_REPEAT_EVENT,
+                    SerialRepeatEvent {
+                        port_name: reader_port_name.clone(),
+                        line: last_line.clone().unwrap_or_default(),
+                        count: repeats,
+                    },
+                );
+                repeats = 0;
+            }
+
+            // Timeouts are per handle, so the reader applies set_serial_read_timeout itself.
+            let wanted_timeout_ms = reader_timeout_ms.load(Ordering::SeqCst);
+            if wanted_timeout_ms != applied_timeout_ms {
+                match reader.set_timeout(Duration::from_millis(wanted_timeout_ms)) {
+                    Ok(()) => applied_timeout_ms = wanted_timeout_ms,
+                    Err(error) => {
+                        emit_serial_line(&app_handle, format!("ERR SERIAL_TIMEOUT {error}"), None);
+                        reader_timeout_ms.store(applied_timeout_ms, Ordering::SeqCst);
+                    }
+                }
+            }
+
+            match reader.read(&mut read_buf) {
+                Ok(size) if size > 0 => {
+                    forward_to_byte_taps(&reader_byte_taps, &read_buf[..size]);
+                    if let Ok(mut window) = reader_data_profile.lock() {
+                        window.record(&read_buf[..size]);
+                    }
+                    // Split on raw bytes and decode per line so multi-byte characters
+                    // straddling a read chunk aren't mangled.
+                    pending.extend_from_slice(&read_buf[..size]);
+                    // Re-read per chunk so set_serial_delimiter applies without reconnecting.
+                    let split_on = reader_delimiter.load(Ordering::SeqCst);
+                    loop {
+                        let line_end = pending.iter().position(|b| *b == split_on);
+                        if cr_progress && split_on != b'\r' {
+                            if let Some(cr) = bare_cr_position(&pending)
+                                .filter(|cr| line_end.is_none_or(|end| *cr < end))
+                            {
+                                let progress_bytes: Vec<u8> = pending.drain(..=cr).take(cr).collect();
+                                let text = String::from_utf8_lossy(&progress_bytes).trim().to_string();
+                                if !text.is_empty() {
+                                    emit_backend_event(
+                                        &app_handle,
+                                        SERIAL_PROGRESS_EVENT,
+                                        SerialProgressEvent {
+                                            port_name: reader_port_name.clone(),
+                                            text,
+                                        },
+                                    );
+                                }
+                                continue;
+                            }
+                        }
+                        let Some(index) = line_end else {
+                            break;
+                        };
+                        let line_bytes: Vec<u8> = pending.drain(..=index).take(index).collect();
+                        let raw = String::from_utf8_lossy(&line_bytes).trim().to_string();
+                        if !raw.is_empty() {
+                            if let Some(banner) = ready_banner.as_deref() {
+                                if raw.contains(banner) {
+                                    mark_serial_ready(&app_handle, &reader_writes_ready, &reader_port_name, "banner");
+                                }
+                            }
+                            forward_to_line_taps(&reader_taps, &raw);
+                            if !repeat_window.is_zero() && last_line.as_deref() == Some(raw.as_str()) {
+                                if repeats == 0 {
+                                    repeats_since = std::time::Instant::now();
+                                }
+                                repeats += 1;
+                                continue;
+                            }
+                            if repeats > 0 {
+                                emit_backend_event(
+                                    &app_handle,
+                                    SERIAL_REPEAT_EVENT,
+                                    SerialRepeatEvent {
+                                        port_name: reader_port_name.clone(),
+                                        line: last_line.clone().unwrap_or_default(),
+                                        count: repeats,
+                                    },
+                                );
+                                repeats = 0;
+                            }
+                            if !repeat_window.is_zero() {
+                                last_line = Some(raw.clone());
+                            }
+                            let raw_hex = debug_raw.then(|| hex_bytes(&line_bytes));
+                            emit_serial_line(&app_handle, raw, raw_hex);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::TimedOut => {}
+                Err(error) => {
+                    emit_serial_line(&app_handle, format!("ERR SERIAL_READ {error}"), None);
+                    if !auto_reconnect {
+                        break;
+                    }
+                    match reopen_serial_after_drop(
+                        &app_handle,
+                        &stop_rx,
+                        &reader_port_name,
+                        reconnect_serial_number.as_deref(),
+                        &reader_baud,
+                        &reader_writer,
+                        max_reconnect_attempts,
+                    ) {
+                        Some((new_reader, new_port_name)) => {
+                            reader = new_reader;
+                            reader_port_name = new_port_name;
+                            reader_connected_at_ms.store(unix_ts_ms() as u64, Ordering::SeqCst);
+                            // The new handle opened with the default timeout; the loop re-applies any override.
+                            applied_timeout_ms = DEFAULT_SERIAL_READ_TIMEOUT_MS;
+                            pending.clear();
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+        reader_pause_ack.park_forever();
+    });
+
+    {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        stop_session_locked(&mut lock);
+        *lock = Some(SerialSession {
+            writer,
+            stop_tx,
+            port_name: port_name.clone(),
+            baud_rate,
+            line_taps,
+            byte_taps,
+            closed: Arc::new(AtomicBool::new(false)),
+            delimiter,
+            read_timeout_ms,
+            reader_pause,
+            writes_ready: writes_ready.clone(),
+            data_profile,
+            connected_at_ms,
+            parity_error_policy,
+            connect_options,
+        });
+        // Published under the session lock so a racing disconnect can't be overwritten.
+        publish_connection_status(
+            state,
+            ConnectionStatus {
+                connected: true,
+                port_name: Some(port_name.clone()),
+                baud_rate: Some(baud),
+                actual_baud,
+            },
+        );
+    }
+    if connect_grace_ms > 0 {
+        let app_handle = app.clone();
+        let port_name = port_name.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(connect_grace_ms));
+            mark_serial_ready(&app_handle, &writes_ready, &port_name, "grace_elapsed");
+        });
+    }
+    record_command_log(
+        state,
+        "serial",
+        "connect",
+        json!({ "port_name": port_name, "baud_rate": baud, "actual_baud": actual_baud }),
+    );
+
+    Ok(ConnectionStatus {
+        connected: true,
+        port_name: Some(port_name),
+        baud_rate: Some(baud),
+        actual_baud,
+    })
+}
+
+// Runs on the reader thread after an I/O error. Returns a fresh reader handle once the port
+// reopens (the writer has already been swapped under its lock, so sends never hit the dead fd),
+// or None if disconnect_serial stopped the session or the attempt budget ran out.
+fn reopen_serial_after_drop(
+    app: &AppHandle,
+    stop_rx: &mpsc::Receiver<()>,
+    port_name: &str,
+    usb_serial_number: Option<&str>,
+    baud: &AtomicU32,
+    writer: &Arc<Mutex<Box<dyn SerialPort + Send>>>,
+    max_attempts: u32,
+) -> Option<(Box<dyn SerialPort>, String)> {
+    let mut delay_ms = SERIAL_RECONNECT_INITIAL_MS;
+    let mut attempt = 0_u32;
+    loop {
+        if max_attempts > 0 && attempt >= max_attempts {
+            break;
+        }
+        attempt += 1;
+        emit_backend_event(
+            app,
+            SERIAL_RECONNECTING_EVENT,
+            SerialReconnectEvent {
+                port_name: port_name.to_string(),
+                attempt,
+                delay_ms: Some(delay_ms),
+            },
+        );
+        match stop_rx.recv_timeout(Duration::from_millis(delay_ms)) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // Deliberate stop (or the session was dropped): not a failure, just leave.
+            _ => return None,
+        }
+        if let Some((port, opened_name)) = open_reconnect_target(port_name, usb_serial_number, baud) {
+            if let Ok(reader) = port.try_clone() {
+                let Ok(mut current) = writer.lock() else {
+                    return None;
+                };
+                *current = port;
+                drop(current);
+                if opened_name != port_name {
+                    rename_serial_session(app, writer, &opened_name);
+                }
+                emit_backend_event(
+                    app,
+                    SERIAL_RECONNECTED_EVENT,
+                    SerialReconnectEvent {
+                        port_name: opened_name.clone(),
+                        attempt,
+                        delay_ms: None,
+                    },
+                );
+                return Some((reader, opened_name));
+            }
+        }
+        delay_ms = (delay_ms * 2).min(SERIAL_RECONNECT_MAX_MS);
+    }
+
+    emit_backend_event(
+        app,
+        SERIAL_RECONNECT_FAILED_EVENT,
+        SerialReconnectEvent {
+            port_name: port_name.to_string(),
+            attempt,
+            delay_ms: None,
+        },
+    );
+    // Close the session cleanly, but only if it is still this one (a new connect may have replaced it).
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut lock) = state.session.lock() {
+            if lock.as_ref().is_some_and(|s| Arc::ptr_eq(&s.writer, writer)) {
+                stop_session_locked(&mut lock);
+                publish_connection_status(&state, ConnectionStatus::default());
+                drop(lock);
+                record_command_log(
+                    &state,
+                    "serial",
+                    "disconnect",
+                    json!({ "port_name": port_name, "reason": "reconnect_exhausted", "attempts": attempt }),
+                );
+            }
+        }
+    }
+    None
+}
+
+// With a known USB serial number, only the port currently carrying that serial number is
+// opened (possibly under a new name), so a different adapter that took over the old path is
+// never adopted. Without one, the original path is all there is to go on.
+fn open_reconnect_target(
+    port_name: &str,
+    usb_serial_number: Option<&str>,
+    baud: &AtomicU32,
+) -> Option<(Box<dyn SerialPort>, String)> {
+    let baud = baud.load(Ordering::SeqCst);
+    let target = match usb_serial_number {
+        Some(serial_number) => usb_port_name_for_serial_number(serial_number)?,
+        None => port_name.to_string(),
+    };
+    let port = open_serial_port(&target, baud).ok()?;
+    Some((port, target))
+}
+
+// Points the live session (if it is still the one owning `writer`) at a re-enumerated path.
+fn rename_serial_session(app: &AppHandle, writer: &Arc<Mutex<Box<dyn SerialPort + Send>>>, new_port_name: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let Ok(mut lock) = state.session.lock() else {
+        return;
+    };
+    let Some(session) = lock.as_mut().filter(|s| Arc::ptr_eq(&s.writer, writer)) else {
+        return;
+    };
+    let old_port_name = std::mem::replace(&mut session.port_name, new_port_name.to_string());
+    let mut status = state
+        .connection_status
+        .read()
+        .map(|status| status.clone())
+        .unwrap_or_default();
+    status.port_name = Some(new_port_name.to_string());
+    publish_connection_status(&state, status);
+    drop(lock);
+    record_command_log(
+        &state,
+        "serial",
+        "rebind",
+        json!({ "from": old_port_name, "to": new_port_name, "reason": "usb_serial_match" }),
+    );
+}
+
+#[tauri::command]
+fn rebind_serial(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    new_port_name: String,
+) -> Result<ConnectionStatus, String> {
+    let new_port_name = new_port_name.trim().to_string();
+    if new_port_name.is_empty() {
+        return Err("new_port_name cannot be empty".to_string());
+    }
+    let session = active_serial_session(&state)?;
+    if session.port_name == new_port_name {
+        return Err(format!("Serial session is already bound to {new_port_name}"));
+    }
+    let baud = session.baud_rate.load(Ordering::SeqCst);
+    let port = open_serial_port(&new_port_name, baud)
+        .map_err(|error| format!("Failed to open serial port {new_port_name}: {error}"))?;
+    record_command_log(
+        &state,
+        "serial",
+        "rebind",
+        json!({ "from": session.port_name, "to": new_port_name, "reason": "manual" }),
+    );
+    let reader = clone_serial_reader(port.as_ref())?;
+    // Replaces the old session (stopping its reader) while keeping baud and connect options.
+    start_serial_session(&app, &state, port, reader, new_port_name, baud, session.connect_options)
+}
+
+#[tauri::command]
+async fn probe_supported_bauds(
+    state: State<'_, AppState>,
+    port_name: String,
+    candidates: Vec<u32>,
+    operation_id: Option<String>,
+) -> Result<Vec<BaudProbeResult>, String> {
+    let port_name = port_name.trim().to_string();
+    if port_name.is_empty() {
+        return Err("port_name cannot be empty".to_string());
+    }
+    if candidates.is_empty() {
+        return Err("candidates must contain at least one baud rate".to_string());
+    }
+    {
+        let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        if lock.as_ref().is_some_and(|s| s.port_name == port_name) {
+            return Err(format!("{port_name} is held by the active session; disconnect before probing"));
+        }
+    }
+
+    let operation = begin_operation(&state, operation_id)?;
+    let mut results = Vec::with_capacity(candidates.len());
+    for requested in candidates {
+        operation.check()?;
+        // Each trial opens and drops its own handle so the port is released between candidates.
+        // Same open path (timeout, EACCES retries) as connect and reconnect.
+        let result = match open_serial_port(&port_name, requested) {
+            Ok(port) => match port.baud_rate() {
+                Ok(actual) => BaudProbeResult {
+                    requested,
+                    actual: Some(actual),
+                    supported: actual == requested,
+                    error: None,
+                },
+                Err(error) => BaudProbeResult {
+                    requested,
+                    actual: None,
+                    supported: false,
+                    error: Some(format!("Failed to read back baud: {error}")),
+                },
+            },
+            Err(error) => BaudProbeResult {
+                requested,
+                actual: None,
+                supported: false,
+                error: Some(format!("Failed to open serial port {port_name}: {error}")),
+            },
+        };
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+fn get_connect_diagnostics(state: State<'_, AppState>) -> Result<Option<ConnectDiagnostics>, String> {
+    let lock = state
+        .last_connect_failure
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    Ok(lock.clone())
+}
+
+// Same character set Tauri accepts for event names.
+fn validate_event_name(name: &str) -> Result<(), String> {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'))
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid event name '{name}': use letters, digits, '-', '/', ':' or '_'"
+        ))
+    }
+}
+
+#[tauri::command]
+fn export_config(app: AppHandle, state: State<'_, AppState>) -> Result<ConfigSnapshot, String> {
+    let serial_event_name = state
+        .serial_event_name
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?
+        .clone();
+    Ok(ConfigSnapshot {
+        version: CONFIG_SNAPSHOT_VERSION,
+        serial_event_name,
+        unified_events: state.unified_events.load(Ordering::Relaxed),
+        stdout_mirror: state.stdout_mirror.load(Ordering::Relaxed),
+        device_profiles: load_device_profiles(&app)?,
+        connection_profiles: load_config_map(&app, CONNECTION_PROFILES_FILE)?,
+        serial_aliases: state
+            .serial_aliases
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?
+            .clone(),
+        serial_alias_prefix: state
+            .serial_alias_prefix
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?
+            .clone(),
+    })
+}
+
+#[tauri::command]
+fn import_config(app: AppHandle, state: State<'_, AppState>, blob: Value) -> Result<ConfigSnapshot, String> {
+    let version = blob
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Config blob is missing a numeric version".to_string())?;
+    if version != u64::from(CONFIG_SNAPSHOT_VERSION) {
+        return Err(format!(
+            "Unsupported config version {version}; this build reads version {CONFIG_SNAPSHOT_VERSION}"
+        ));
+    }
+    let snapshot: ConfigSnapshot =
+        serde_json::from_value(blob).map_err(|e| format!("Invalid config blob: {e}"))?;
+    // Validate everything before touching disk or state so a bad blob changes nothing.
+    let serial_event_name = snapshot
+        .serial_event_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    if let Some(name) = &serial_event_name {
+        validate_event_name(name)?;
+    }
+    // Same rules as save_named_profile / save_device_profile; both maps are looked up by key,
+    // so the key must be exactly what those commands would have stored it under.
+    for (key, profile) in &snapshot.connection_profiles {
+        if profile.transport != "serial" {
+            return Err(format!(
+                "Profile '{}' uses unsupported transport '{}'",
+                profile.name, profile.transport
+            ));
+        }
+        if key.trim().is_empty() || *key != profile.name {
+            return Err(format!("Profile key '{key}' does not match its name '{}'", profile.name));
+        }
+        if profile.port_name.trim().is_empty() {
+            return Err(format!("Profile '{key}': portName cannot be empty"));
+        }
+    }
+    for (key, profile) in &snapshot.device_profiles {
+        validate_device_profile(&profile.serial_number, profile.baud_rate)
+            .map_err(|e| format!("Device profile '{key}': {e}"))?;
+        if key != profile.serial_number.trim() {
+            return Err(format!(
+                "Device profile key '{key}' does not match its serial number '{}'",
+                profile.serial_number
+            ));
+        }
+    }
+    let serial_aliases = snapshot
+        .serial_aliases
+        .into_iter()
+        .map(|(name, lines)| normalize_serial_alias(&name, lines))
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+    let serial_alias_prefix = snapshot
+        .serial_alias_prefix
+        .as_deref()
+        .map(normalize_alias_prefix)
+        .transpose()?;
+
+    store_device_profiles(&app, &snapshot.device_profiles)?;
+    store_config_map(&app, CONNECTION_PROFILES_FILE, &snapshot.connection_profiles)?;
+    *state
+        .serial_event_name
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = serial_event_name.clone();
+    state.unified_events.store(snapshot.unified_events, Ordering::Relaxed);
+    state.stdout_mirror.store(snapshot.stdout_mirror, Ordering::Relaxed);
+    *state
+        .serial_aliases
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = serial_aliases.clone();
+    *state
+        .serial_alias_prefix
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = serial_alias_prefix.clone();
+    append_desktop_audit_log(
+        "config.import",
+        &json!({
+            "device_profiles": snapshot.device_profiles.len(),
+            "connection_profiles": snapshot.connection_profiles.len()
+        }),
+    );
+    Ok(ConfigSnapshot {
+        serial_event_name,
+        serial_aliases,
+        serial_alias_prefix,
+        ..snapshot
+    })
+}
+
+#[tauri::command]
+fn set_serial_event_name(state: State<'_, AppState>, name: Option<String>) -> Result<String, String> {
+    let name = name.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(name) = &name {
+        validate_event_name(name)?;
+    }
+    let mut lock = state.serial_event_name.lock().map_err(|_| "State lock poisoned".to_string())?;
+    *lock = name;
+    Ok(lock.clone().unwrap_or_else(|| SERIAL_EVENT.to_string()))
+}
+
+#[tauri::command]
+fn get_event_log(state: State<'_, AppState>, n: Option<usize>) -> Result<Vec<BackendEventRecord>, String> {
+    let log = state.event_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+    let n = n.unwrap_or(EVENT_LOG_CAPACITY).min(log.len());
+    Ok(log.iter().skip(log.len() - n).cloned().collect())
+}
+
+#[tauri::command]
+fn clear_event_log(state: State<'_, AppState>) -> Result<usize, String> {
+    let mut log = state.event_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+    let cleared = log.len();
+    log.clear();
+    Ok(cleared)
+}
+
+#[tauri::command]
+fn set_unified_events(state: State<'_, AppState>, enabled: bool) -> Result<bool, String> {
+    state.unified_events.store(enabled, Ordering::Relaxed);
+    Ok(enabled)
+}
+
+#[tauri::command]
+fn set_stdout_mirror(state: State<'_, AppState>, enabled: bool) -> Result<bool, String> {
+    state.stdout_mirror.store(enabled, Ordering::Relaxed);
+    Ok(enabled)
+}
+
+#[tauri::command]
+fn get_serial_data_profile(state: State<'_, AppState>) -> Result<SerialDataProfile, String> {
+    let session = active_serial_session(&state)?;
+    let window = session
+        .data_profile
+        .lock()
+        .map_err(|_| "Serial data profile lock poisoned".to_string())?;
+    let sample_bytes = window.recent.len();
+    if sample_bytes == 0 {
+        return Ok(SerialDataProfile {
+            sample_bytes,
+            printable_ratio: None,
+            verdict: "unknown".to_string(),
+        });
+    }
+    let ratio = window.printable as f64 / sample_bytes as f64;
+    let verdict = if sample_bytes < DATA_PROFILE_MIN_SAMPLE {
+        "unknown"
+    } else if ratio >= DATA_PROFILE_TEXT_RATIO {
+        "text"
+    } else {
+        "binary"
+    };
+    Ok(SerialDataProfile {
+        sample_bytes,
+        printable_ratio: Some(ratio),
+        verdict: verdict.to_string(),
+    })
+}
+
+#[tauri::command]
+fn get_serial_parity_policy(state: State<'_, AppState>) -> Result<SerialParityPolicyStatus, String> {
+    let session = active_serial_session(&state)?;
+    Ok(SerialParityPolicyStatus {
+        port_name: session.port_name.clone(),
+        policy: session.parity_error_policy.clone(),
+        supported_policies: ["ignore", "mark", "replace"]
+            .into_iter()
+            .filter(|p| serial_parity_policy_supported(p).is_ok())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+#[tauri::command]
+fn get_serial_uptime(state: State<'_, AppState>) -> Result<SerialUptime, String> {
+    let session = active_serial_session(&state)?;
+    let connected_at_ms = session.connected_at_ms.load(Ordering::SeqCst);
+    Ok(SerialUptime {
+        port_name: session.port_name.clone(),
+        connected_at_ms: u128::from(connected_at_ms),
+        uptime_secs: (unix_ts_ms().saturating_sub(u128::from(connected_at_ms))) as f64 / 1000.0,
+    })
+}
+
+#[tauri::command]
+fn get_serial_buffer_status(state: State<'_, AppState>) -> Result<SerialBufferStatus, String> {
+    let session = active_serial_session(&state)?;
+    let writer = session.writer.lock().map_err(|_| "Serial writer lock poisoned".to_string())?;
+    let bytes_to_read = writer
+        .bytes_to_read()
+        .map_err(|e| format!("Failed to query serial input buffer: {e}"))?;
+    let bytes_to_write = writer
+        .bytes_to_write()
+        .map_err(|e| format!("Failed to query serial output buffer: {e}"))?;
+    Ok(SerialBufferStatus {
+        port_name: session.port_name.clone(),
+        bytes_to_read,
+        bytes_to_write,
+    })
+}
+
+#[tauri::command]
+async fn diagnose_port(state: State<'_, AppState>, port_name: String) -> Result<PortDiagnosis, String> {
+    let port_name = port_name.trim().to_string();
+    if port_name.is_empty() {
+        return Err("port_name cannot be empty".to_string());
+    }
+    let mut diagnosis = PortDiagnosis {
+        port_name: port_name.clone(),
+        ..Default::default()
+    };
+
+    if let Some(port) = serialport::available_ports()
+        .ok()
+        .and_then(|ports| ports.into_iter().find(|p| p.port_name == port_name))
+    {
+        diagnosis.enumerated = true;
+        diagnosis.port_type = Some(port_type_name(&port.port_type));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match std::fs::metadata(&port_name) {
+            Ok(meta) => {
+                diagnosis.device_node_exists = Some(true);
+                diagnosis.mode = Some(format!("{:o}", meta.mode() & 0o7777));
+                diagnosis.owner_uid = Some(meta.uid());
+                diagnosis.owner_gid = Some(meta.gid());
+            }
+            Err(_) => diagnosis.device_node_exists = Some(false),
+        }
+    }
+
+    {
+        let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        diagnosis.held_by_this_app = lock.as_ref().is_some_and(|s| s.port_name == port_name);
+    }
+    // Opening a port we already hold would fail (or steal it), so skip the test.
+    if !diagnosis.held_by_this_app {
+        match serialport::new(&port_name, DEFAULT_SERIAL_BAUD)
+            .timeout(Duration::from_millis(DEFAULT_SERIAL_READ_TIMEOUT_MS))
+            .open()
+        {
+            Ok(_) => diagnosis.openable = Some(true),
+            Err(error) => {
+                diagnosis.openable = Some(false);
+                let message = format!("Failed to open serial port {port_name}: {error}");
+                diagnosis.open_error = Some(diagnose_serial_open_error(&port_name, &error, message));
+            }
+        }
+    }
+
+    diagnosis.verdict = if diagnosis.held_by_this_app {
+        "held_by_this_app".to_string()
+    } else if let Some(open_error) = &diagnosis.open_error {
+        open_error.category.clone()
+    } else if !diagnosis.enumerated {
+        // Opens fine but the enumerator doesn't list it (e.g. a pty or a udev rule mismatch).
+        "not_enumerated".to_string()
+    } else {
+        "ok".to_string()
+    };
+    Ok(diagnosis)
+}
+
+#[tauri::command]
+fn get_serial_driver_info(state: State<'_, AppState>) -> Result<SerialDriverInfo, String> {
+    let session = active_serial_session(&state)?;
+    let mut info = SerialDriverInfo {
+        port_name: session.port_name.clone(),
+        platform: std::env::consts::OS.to_string(),
+        ..Default::default()
+    };
+
+    // Portable part: whatever the serialport enumerator knows about the USB device.
+    if let Some(port) = serialport::available_ports()
+        .ok()
+        .and_then(|ports| ports.into_iter().find(|p| p.port_name == session.port_name))
+    {
+        if let serialport::SerialPortType::UsbPort(usb) = port.port_type {
+            info.vid = Some(usb.vid);
+            info.pid = Some(usb.pid);
+            info.manufacturer = usb.manufacturer;
+            info.product = usb.product;
+            info.serial_number = usb.serial_number;
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fill_linux_driver_info(&mut info);
+
+    Ok(info)
+}
+
+fn validate_device_profile(serial_number: &str, baud_rate: u32) -> Result<(), String> {
+    if serial_number.trim().is_empty() {
+        return Err("serial_number cannot be empty".to_string());
+    }
+    if baud_rate == 0 {
+        return Err("baud_rate must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn save_device_profile(
+    app: AppHandle,
+    serial_number: String,
+    label: Option<String>,
+    baud_rate: u32,
+) -> Result<DeviceSerialProfile, String> {
+    let serial_number = serial_number.trim().to_string();
+    validate_device_profile(&serial_number, baud_rate)?;
+    let profile = DeviceSerialProfile {
+        serial_number: serial_number.clone(),
+        label: label.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        baud_rate,
+    };
+    let mut profiles = load_device_profiles(&app)?;
+    profiles.insert(serial_number, profile.clone());
+    store_device_profiles(&app, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+fn list_device_profiles(app: AppHandle) -> Result<Vec<DeviceSerialProfile>, String> {
+    Ok(load_device_profiles(&app)?.into_values().collect())
+}
+
+#[tauri::command]
+fn delete_device_profile(app: AppHandle, serial_number: String) -> Result<bool, String> {
+    let mut profiles = load_device_profiles(&app)?;
+    let removed = profiles.remove(serial_number.trim()).is_some();
+    if removed {
+        store_device_profiles(&app, &profiles)?;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+fn save_named_profile(app: AppHandle, name: String, settings: Value) -> Result<NamedConnectionProfile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    // Profiles are plain JSON on disk, so anything secret-looking is dropped before parsing.
+    let settings = match settings {
+        Value::Object(mut map) => {
+            map.retain(|key, _| !is_secret_key(key));
+            map.insert("name".to_string(), Value::String(name.clone()));
+            map.entry("transport".to_string())
+                .or_insert_with(|| Value::String("serial".to_string()));
+            Value::Object(map)
+        }
+        _ => return Err("Profile settings must be a JSON object".to_string()),
+    };
+    let profile: NamedConnectionProfile =
+        serde_json::from_value(settings).map_err(|e| format!("Invalid profile settings: {e}"))?;
+    if profile.transport != "serial" {
+        return Err(format!("Unsupported transport '{}': only serial is available", profile.transport));
+    }
+    if profile.port_name.trim().is_empty() {
+        return Err("Profile portName cannot be empty".to_string());
+    }
+    let mut profiles: BTreeMap<String, NamedConnectionProfile> = load_config_map(&app, CONNECTION_PROFILES_FILE)?;
+    profiles.insert(name, profile.clone());
+    store_config_map(&app, CONNECTION_PROFILES_FILE, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+fn list_named_profiles(app: AppHandle) -> Result<Vec<NamedConnectionProfile>, String> {
+    let profiles: BTreeMap<String, NamedConnectionProfile> = load_config_map(&app, CONNECTION_PROFILES_FILE)?;
+    Ok(profiles.into_values().collect())
+}
+
+#[tauri::command]
+fn delete_named_profile(app: AppHandle, name: String) -> Result<bool, String> {
+    let mut profiles: BTreeMap<String, NamedConnectionProfile> = load_config_map(&app, CONNECTION_PROFILES_FILE)?;
+    let removed = profiles.remove(name.trim()).is_some();
+    if removed {
+        store_config_map(&app, CONNECTION_PROFILES_FILE, &profiles)?;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+fn connect_profile(app: AppHandle, state: State<'_, AppState>, name: String) -> Result<ConnectionStatus, String> {
+    let mut profiles: BTreeMap<String, NamedConnectionProfile> = load_config_map(&app, CONNECTION_PROFILES_FILE)?;
+    let profile = profiles
+        .remove(name.trim())
+        .ok_or_else(|| format!("No connection profile named '{}'", name.trim()))?;
+    if profile.transport != "serial" {
+        return Err(format!("Unsupported transport '{}': only serial is available", profile.transport));
+    }
+    // Switching robots: drop whatever is connected before opening the profile's port.
+    {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        stop_session_locked(&mut lock);
+        publish_connection_status(&state, ConnectionStatus::default());
+    }
+    connect_serial(app, state, profile.port_name, profile.baud_rate, Some(profile.options)).map_err(|error| error.message)
+}
+
+#[tauri::command]
+fn disconnect_serial(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
+    let port_name = {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        let port_name = lock.as_ref().map(|s| s.port_name.clone());
+        stop_session_locked(&mut lock);
+        publish_connection_status(&state, ConnectionStatus::default());
+        port_name
+    };
+    if let Some(port_name) = port_name {
+        record_command_log(&state, "serial", "disconnect", json!({ "port_name": port_name }));
+    }
+
+    Ok(ConnectionStatus::default())
+}
+
+#[tauri::command]
+fn get_connection_status(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
+    let snapshot = state
+        .connection_status
+        .read()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    Ok(snapshot.clone())
+}
+
+// Ok(None) for anything that isn't a known alias; such lines are sent unchanged.
+fn expand_serial_alias(state: &AppState, line: &str) -> Result<Option<Vec<String>>, String> {
+    let prefix = state
+        .serial_alias_prefix
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SERIAL_ALIAS_PREFIX.to_string());
+    let Some(name) = line.strip_prefix(prefix.as_str()) else {
+        return Ok(None);
+    };
+    let aliases = state.serial_aliases.lock().map_err(|_| "State lock poisoned".to_string())?;
+    Ok(aliases.get(name.trim()).cloned())
+}
+
+// Trims the name and lines, drops blank lines, and rejects anything define_serial_alias can't store.
+fn normalize_serial_alias(name: &str, lines: Vec<String>) -> Result<(String, Vec<String>), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err("Alias name must be a single non-empty token".to_string());
+    }
+    let lines: Vec<String> = lines
+        .into_iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Err(format!("Alias '{name}' must expand to at least one line"));
+    }
+    Ok((name, lines))
+}
+
+fn normalize_alias_prefix(prefix: &str) -> Result<String, String> {
+    let prefix = prefix.trim().to_string();
+    if prefix.is_empty() {
+        return Err("Alias prefix cannot be empty".to_string());
+    }
+    Ok(prefix)
+}
+
+#[tauri::command]
+fn define_serial_alias(state: State<'_, AppState>, name: String, lines: Vec<String>) -> Result<Vec<String>, String> {
+    let (name, lines) = normalize_serial_alias(&name, lines)?;
+    let mut aliases = state.serial_aliases.lock().map_err(|_| "State lock poisoned".to_string())?;
+    aliases.insert(name, lines.clone());
+    Ok(lines)
+}
+
+#[tauri::command]
+fn remove_serial_alias(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    let mut aliases = state.serial_aliases.lock().map_err(|_| "State lock poisoned".to_string())?;
+    Ok(aliases.remove(name.trim()).is_some())
+}
+
+#[tauri::command]
+fn list_serial_aliases(state: State<'_, AppState>) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let aliases = state.serial_aliases.lock().map_err(|_| "State lock poisoned".to_string())?;
+    Ok(aliases.clone())
+}
+
+#[tauri::command]
+fn set_serial_alias_prefix(state: State<'_, AppState>, prefix: String) -> Result<String, String> {
+    let prefix = normalize_alias_prefix(&prefix)?;
+    let mut lock = state
+        .serial_alias_prefix
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    *lock = Some(prefix.clone());
+    Ok(prefix)
+}
+
+fn serial_line_ending(line_ending: Option<&str>) -> Result<&'static str, String> {
+    match line_ending.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("lf") => Ok("\n"),
+        Some("crlf") => Ok("\r\n"),
+        Some("cr") => Ok("\r"),
+        Some("none") => Ok(""),
+        Some(other) => Err(format!("Unknown line_ending '{other}'; expected lf, crlf, cr or none")),
+    }
+}
+
+#[tauri::command]
+fn send_serial_line(
+    state: State<'_, AppState>,
+    line: String,
+    append_newline: Option<bool>,
+    line_ending: Option<String>,
+) -> Result<(), SerialCommandError> {
+    // append_newline=false sends the line verbatim, so a terminator choice would be silently ignored.
+    if append_newline == Some(false) && line_ending.is_some() {
+        return Err("line_ending cannot be combined with append_newline=false; use line_ending=\"none\" instead"
+            .to_string()
+            .into());
+    }
+    let terminator = serial_line_ending(line_ending.as_deref())?;
+    let session = active_serial_session(&state)?;
+    if let Some(expanded) = expand_serial_alias(&state, line.trim())? {
+        // Expanded lines are always separated; with line_ending="none" they are joined by "\n"
+        // and only the final line goes out unterminated.
+        let separator = if terminator.is_empty() { "\n" } else { terminator };
+        for (idx, alias_line) in expanded.iter().enumerate() {
+            let ending = if idx + 1 == expanded.len() { terminator } else { separator };
+            try_write_session_bytes(&session, format!("{}{ending}", alias_line.trim()).as_bytes())?;
+        }
+        record_command_log(&state, "serial", "dispatch", json!({ "alias": line.trim(), "lines": expanded }));
+        return Ok(());
+    }
+    if append_newline.unwrap_or(true) {
+        try_write_session_bytes(&session, format!("{}{terminator}", line.trim()).as_bytes())?;
+    } else {
+        // Partial write of a longer command: send verbatim so separators between pieces survive.
+        try_write_session_bytes(&session, line.as_bytes())?;
+    }
+
+    record_command_log(&state, "serial", "dispatch", json!({ "line": line.trim() }));
+    Ok(())
+}
+
+#[tauri::command]
+async fn send_serial_frames(
+    state: State<'_, AppState>,
+    frames: Vec<Vec<u8>>,
+    gap_ms: u32,
+) -> Result<usize, String> {
+    let session = active_serial_session(&state)?;
+    for (idx, frame) in frames.iter().enumerate() {
+        if idx > 0 && gap_ms > 0 {
+            thread::sleep(Duration::from_millis(u64::from(gap_ms)));
+        }
+        // write_session_bytes flushes, so each frame is on the wire before the gap starts.
+        write_session_bytes(&session, frame)
+            .map_err(|e| format!("Frame {idx} of {}: {e}", frames.len()))?;
+        record_command_log(&state, "serial", "dispatch", json!({ "frame_hex": hex_bytes(frame) }));
+    }
+    Ok(frames.len())
+}
+
+fn serial_injection_enabled() -> bool {
+    // Debug builds always allow it; release builds need the env flag (UI test harness).
+    cfg!(debug_assertions)
+        || std::env::var(SERIAL_INJECT_ENV)
+            .map(|v| v.trim() == "1")
+            .unwrap_or(false)
+}
+
+#[tauri::command]
+fn inject_serial_lines(app: AppHandle, lines: Vec<String>, interval_ms: u64) -> Result<usize, String> {
+    if !serial_injection_enabled() {
+        return Err(format!("inject_serial_lines is disabled; set {SERIAL_INJECT_ENV}=1 to enable"));
+    }
+    let count = lines.len();
+    thread::spawn(move || {
+        for (idx, line) in lines.into_iter().enumerate() {
+            if idx > 0 && interval_ms > 0 {
+                thread::sleep(Duration::from_millis(interval_ms));
+            }
+            emit_serial_line(&app, line, None);
+        }
+    });
+    Ok(count)
+}
+
+#[tauri::command]
+fn set_serial_delimiter(state: State<'_, AppState>, delimiter: u8) -> Result<(), String> {
+    if !delimiter.is_ascii() {
+        return Err(format!("delimiter must be an ASCII byte, got 0x{delimiter:02x}"));
+    }
+    let session = active_serial_session(&state)?;
+    session.delimiter.store(delimiter, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_serial_read_timeout(state: State<'_, AppState>, timeout_ms: u64) -> Result<(), String> {
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_READ_TIMEOUT_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_READ_TIMEOUT_MS}"));
+    }
+    let session = active_serial_session(&state)?;
+    session.read_timeout_ms.store(timeout_ms, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+fn start_serial_pattern(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pattern: String,
+    interval_ms: u64,
+    repeat_count: u32,
+) -> Result<SerialPatternStatus, String> {
+    if pattern.is_empty() {
+        return Err("pattern cannot be empty".to_string());
+    }
+    if interval_ms == 0 {
+        return Err("interval_ms must be greater than 0".to_string());
+    }
+    let session = active_serial_session(&state)?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    {
+        let mut lock = state
+            .serial_pattern_stop
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        stop_serial_pattern_locked(&mut lock);
+        *lock = Some(stop.clone());
+    }
+
+    // `{n}` in the pattern is replaced by the 0-based iteration counter.
+    // repeat_count == 0 runs until stop_serial_pattern or disconnect.
+    let thread_pattern = pattern.clone();
+    thread::spawn(move || {
+        let mut n: u64 = 0;
+        loop {
+            if stop.load(Ordering::SeqCst) || session.closed.load(Ordering::SeqCst) {
+                break;
+            }
+            if repeat_count > 0 && n >= u64::from(repeat_count) {
+                break;
+            }
+            let payload = thread_pattern.replace("{n}", &n.to_string());
+            if let Err(error) = write_session_bytes(&session, payload.as_bytes()) {
+                emit_serial_line(&app, format!("ERR SERIAL_PATTERN {error}"), None);
+                break;
+            }
+            n += 1;
+            thread::sleep(Duration::from_millis(interval_ms));
+        }
+    });
+
+    Ok(SerialPatternStatus {
+        running: true,
+        pattern: Some(pattern),
+        interval_ms: Some(interval_ms),
+        repeat_count: Some(repeat_count),
+    })
+}
+
+#[tauri::command]
+fn stop_serial_pattern(state: State<'_, AppState>) -> Result<SerialPatternStatus, String> {
+    let mut lock = state
+        .serial_pattern_stop
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    stop_serial_pattern_locked(&mut lock);
+    Ok(SerialPatternStatus {
+        running: false,
+        pattern: None,
+        interval_ms: None,
+        repeat_count: None,
+    })
+}
+
+#[tauri::command]
+fn start_serial_tcp_tap(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    bind_addr: String,
+    allow_writes: Option<bool>,
+) -> Result<SerialTcpTapStatus, String> {
+    let requested: SocketAddr = bind_addr
+        .trim()
+        .parse()
+        .map_err(|_| format!("bind_addr must be an IP:port (e.g. 127.0.0.1:7000), got: {}", bind_addr.trim()))?;
+    if !requested.ip().is_loopback() {
+        return Err(format!("bind_addr must be a loopback address, got: {requested}"));
+    }
+    let allow_writes = allow_writes.unwrap_or(false);
+    let session = active_serial_session(&state)?;
+
+    // Held until the new tap is registered; the old tap is fully stopped first so restarting
+    // on the same bind_addr doesn't hit "address in use".
+    let mut tap_slot = state
+        .serial_tcp_tap
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    stop_serial_tcp_tap_locked(&mut tap_slot);
+
+    let listener = TcpListener::bind(requested).map_err(|e| format!("Failed to bind {requested}: {e}"))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure listener on {requested}: {e}"))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read listener address: {e}"))?;
+    let rx = register_byte_tap(&session)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let clients: Arc<Mutex<Vec<TcpStream>>> = Arc::new(Mutex::new(Vec::new()));
+
+    // Accept loop: new clients receive rx bytes; with allow_writes their input goes to the device.
+    let accept_thread = {
+        let stop = stop.clone();
+        let session = session.clone();
+        let clients = clients.clone();
+        thread::spawn(move || loop {
+            if stop.load(Ordering::SeqCst) || session.closed.load(Ordering::SeqCst) {
+                break;
+            }
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let _ = stream.set_nodelay(true);
+                    if stream
+                        .set_write_timeout(Some(Duration::from_millis(SERIAL_TCP_TAP_WRITE_TIMEOUT_MS)))
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    if allow_writes {
+                        if let Ok(mut client_reader) = stream.try_clone() {
+                            let stop = stop.clone();
+                            let session = session.clone();
+                            let app = app.clone();
+                            thread::spawn(move || {
+                                let _ = client_reader.set_read_timeout(Some(Duration::from_millis(CANCEL_POLL_MS)));
+                                let mut buf = [0_u8; 512];
+                                loop {
+                                    if stop.load(Ordering::SeqCst) || session.closed.load(Ordering::SeqCst) {
+                                        break;
+                                    }
+                                    match client_reader.read(&mut buf) {
+                                        Ok(0) => break,
+                                        // Same path as send_serial_line: honours the connect grace and is logged.
+                                        Ok(_) if !session.writes_ready.load(Ordering::SeqCst) => {}
+                                        Ok(size) => {
+                                            if try_write_session_bytes(&session, &buf[..size]).is_err() {
+                                                break;
+                                            }
+                                            if let Some(state) = app.try_state::<AppState>() {
+                                                record_command_log(
+                                                    &state,
+                                                    "serial",
+                                                    "dispatch",
+                                                    json!({ "tcp_tap": hex_bytes(&buf[..size]) }),
+                                                );
+                                            }
+                                        }
+                                        Err(error)
+                                            if error.kind() == std::io::ErrorKind::WouldBlock
+                                                || error.kind() == std::io::ErrorKind::TimedOut => {}
+                                        Err(_) => break,
+                                    }
+                                }
+                            });
+                        }
+                    }
+                    if let Ok(mut lock) = clients.lock() {
+                        lock.push(stream);
+                    }
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(_) => thread::sleep(Duration::from_millis(200)),
+            }
+        })
+    };
+    *tap_slot = Some(SerialTcpTap {
+        stop: stop.clone(),
+        accept_thread,
+    });
+    drop(tap_slot);
+
+    // Fan-out loop: every received chunk goes to every connected client.
+    {
+        let stop = stop.clone();
+        let session = session.clone();
+        thread::spawn(move || {
+            loop {
+                if stop.load(Ordering::SeqCst) || session.closed.load(Ordering::SeqCst) {
+                    break;
+                }
+                match rx.recv_timeout(Duration::from_millis(CANCEL_POLL_MS)) {
+                    Ok(bytes) => {
+                        // Write outside the lock so accepting new clients never waits on a slow one;
+                        // a client that errors or hits the write timeout is dropped.
+                        let mut batch = match clients.lock() {
+                            Ok(mut lock) => std::mem::take(&mut *lock),
+                            Err(_) => break,
+                        };
+                        batch.retain_mut(|client| client.write_all(&bytes).is_ok());
+                        if let Ok(mut lock) = clients.lock() {
+                            lock.extend(batch);
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            if let Ok(mut lock) = clients.lock() {
+                for client in lock.drain(..) {
+                    let _ = client.shutdown(std::net::Shutdown::Both);
+                }
+            }
+        });
+    }
+
+    Ok(SerialTcpTapStatus {
+        running: true,
+        bind_addr: Some(local_addr.to_string()),
+        allow_writes,
+    })
+}
+
+#[tauri::command]
+fn stop_serial_tcp_tap(state: State<'_, AppState>) -> Result<SerialTcpTapStatus, String> {
+    let mut lock = state
+        .serial_tcp_tap
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?;
+    stop_serial_tcp_tap_locked(&mut lock);
+    Ok(SerialTcpTapStatus {
+        running: false,
+        bind_addr: None,
+        allow_writes: false,
+    })
+}
+
+#[tauri::command]
+async fn capture_serial_window(
+    state: State<'_, AppState>,
+    duration_ms: u64,
+    operation_id: Option<String>,
+) -> Result<Vec<CapturedSerialLine>, String> {
+    if duration_ms == 0 || duration_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("duration_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    let rx = register_line_tap(&session)?;
+
+    // Lines keep flowing to SERIAL_EVENT; this only tees them for the window.
+    let deadline = std::time::Instant::now() + Duration::from_millis(duration_ms);
+    let mut captured: Vec<CapturedSerialLine> = Vec::new();
+    loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        operation.check()?;
+        match rx.recv_timeout(remaining.min(Duration::from_millis(CANCEL_POLL_MS))) {
+            Ok(line) => captured.push(line),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Ok(captured)
+}
+
+#[tauri::command]
+async fn read_device_config(
+    state: State<'_, AppState>,
+    request_line: String,
+    end_marker: String,
+    timeout_ms: u64,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    if request_line.trim().is_empty() {
+        return Err("request_line cannot be empty".to_string());
+    }
+    let end_marker = end_marker.trim().to_string();
+    if end_marker.is_empty() {
+        return Err("end_marker cannot be empty".to_string());
+    }
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    let lines = send_and_collect_until(
+        &session,
+        &operation,
+        &request_line,
+        &end_marker,
+        Duration::from_millis(timeout_ms),
+    )?;
+    Ok(lines.join("\n"))
+}
+
+#[tauri::command]
+async fn negotiate_serial_baud(
+    state: State<'_, AppState>,
+    handshake_line: String,
+    ack_substring: String,
+    new_baud: u32,
+    timeout_ms: Option<u64>,
+    operation_id: Option<String>,
+) -> Result<ConnectionStatus, String> {
+    if handshake_line.trim().is_empty() {
+        return Err("handshake_line cannot be empty".to_string());
+    }
+    if ack_substring.trim().is_empty() {
+        return Err("ack_substring cannot be empty".to_string());
+    }
+    if new_baud == 0 {
+        return Err("new_baud must be greater than 0".to_string());
+    }
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_BAUD_NEGOTIATION_TIMEOUT_MS);
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    send_and_collect_until(
+        &session,
+        &operation,
+        &handshake_line,
+        ack_substring.trim(),
+        Duration::from_millis(timeout_ms),
+    )
+    .map_err(|e| format!("Baud negotiation failed: {e}"))?;
+
+    // Park the reader so bytes straddling the switch don't get stitched into a line,
+    // then flush whatever arrived at the old rate.
+    let pause = pause_serial_reader(&session)?;
+    let switched = {
+        let mut writer = session
+            .writer
+            .lock()
+            .map_err(|_| "Serial writer lock poisoned".to_string())?;
+        writer
+            .set_baud_rate(new_baud)
+            .map_err(|e| format!("Failed to switch to {new_baud} baud: {e}"))
+            .map(|_| {
+                let _ = writer.clear(serialport::ClearBuffer::Input);
+                writer.baud_rate().ok()
+            })
+    };
+    drop(pause);
+    let actual_baud = switched?;
+    session.baud_rate.store(new_baud, Ordering::SeqCst);
+
+    // Published under the session lock, and only if a disconnect hasn't replaced this session meanwhile.
+    let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+    let Some(current) = lock.as_ref().filter(|s| Arc::ptr_eq(&s.writer, &session.writer)) else {
+        return Err(format!("Serial session on {} closed during baud negotiation", session.port_name));
+    };
+    let status = ConnectionStatus {
+        connected: true,
+        port_name: Some(current.port_name.clone()),
+        baud_rate: Some(new_baud),
+        actual_baud,
+    };
+    publish_connection_status(&state, status.clone());
+    Ok(status)
+}
+
+// Blocks until the reader thread has finished any in-flight read and parked. The pause
+// lasts until the returned guard is dropped; overlapping pauses nest.
+fn pause_serial_reader(session: &SerialSession) -> Result<ReaderPauseGuard, String> {
+    let pause = session.reader_pause.clone();
+    pause.requests.fetch_add(1, Ordering::SeqCst);
+    let guard = ReaderPauseGuard { pause: pause.clone() };
+    let wait = Duration::from_millis(session.read_timeout_ms.load(Ordering::SeqCst) + READER_PAUSE_ACK_MARGIN_MS);
+    let parked = pause.parked.lock().map_err(|_| "Serial reader pause lock poisoned".to_string())?;
+    let (parked, _) = pause
+        .parked_changed
+        .wait_timeout_while(parked, wait, |parked| !*parked)
+        .map_err(|_| "Serial reader pause lock poisoned".to_string())?;
+    if !*parked {
+        return Err(format!("Serial reader did not pause within {} ms", wait.as_millis()));
+    }
+    Ok(guard)
+}
+
+// Drops input at the OS buffer until the line has been quiet for quiet_for.
+// Returns (bytes discarded, went quiet before hard_deadline).
+fn drain_paused_input(
+    session: &SerialSession,
+    operation: &OperationGuard<'_>,
+    quiet_for: Duration,
+    hard_deadline: std::time::Instant,
+) -> Result<(u64, bool), String> {
+    let mut discarded = 0_u64;
+    let mut last_data = std::time::Instant::now();
+    loop {
+        operation.check()?;
+        let now = std::time::Instant::now();
+        if now.duration_since(last_data) >= quiet_for {
+            return Ok((discarded, true));
+        }
+        if now >= hard_deadline {
+            return Ok((discarded, false));
+        }
+        let pending = {
+            let writer = session
+                .writer
+                .lock()
+                .map_err(|_| "Serial writer lock poisoned".to_string())?;
+            let pending = writer
+                .bytes_to_read()
+                .map_err(|e| format!("Failed to query serial input buffer: {e}"))?;
+            if pending > 0 {
+                writer
+                    .clear(serialport::ClearBuffer::Input)
+                    .map_err(|e| format!("Failed to clear serial input: {e}"))?;
+            }
+            pending
+        };
+        if pending > 0 {
+            discarded += u64::from(pending);
+            last_data = std::time::Instant::now();
+        } else {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[tauri::command]
+async fn serial_interact(
+    state: State<'_, AppState>,
+    send: String,
+    read_until: String,
+    timeout_ms: u64,
+    operation_id: Option<String>,
+) -> Result<String, String> {
+    if read_until.is_empty() {
+        return Err("read_until cannot be empty".to_string());
+    }
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    // Menu prompts usually don't end in a newline, so match on raw bytes rather than lines.
+    let rx = register_byte_tap(&session)?;
+    write_session_bytes(&session, format!("{}\n", send.trim()).as_bytes())?;
+    record_command_log(&state, "serial", "dispatch", json!({ "line": send.trim() }));
+
+    let deadline = std::time::Instant::now() + Duration::from_millis(timeout_ms);
+    let mut collected: Vec<u8> = Vec::new();
+    loop {
+        operation.check()?;
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return Err(format!(
+                "Timed out after {timeout_ms}ms waiting for {read_until} ({} bytes received)",
+                collected.len()
+            ));
+        }
+        match rx.recv_timeout(remaining.min(Duration::from_millis(CANCEL_POLL_MS))) {
+            Ok(chunk) => {
+                collected.extend_from_slice(&chunk);
+                let text = String::from_utf8_lossy(&collected);
+                if let Some(pos) = text.find(&read_until) {
+                    return Ok(text[..pos + read_until.len()].to_string());
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err("Serial session closed while waiting for response".to_string());
+            }
+        }
+    }
+}
+
+// The serial reader trims both ends of every received line, so expected code is trimmed the
+// same way; indentation can't be verified over this path. Blank lines are not stored.
+fn normalize_code_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    lines.map(str::trim).filter(|line| !line.is_empty()).collect()
+}
+
+fn compare_code_lines(expected: &str, received: &[String]) -> CodeVerifyResult {
+    let expected_lines = normalize_code_lines(expected.lines());
+    let received_lines = normalize_code_lines(received.iter().map(String::as_str));
+    let mut matched_lines = Vec::new();
+    let mut mismatched_lines = Vec::new();
+    for idx in 0..expected_lines.len().max(received_lines.len()) {
+        if expected_lines.get(idx) == received_lines.get(idx) {
+            matched_lines.push(idx + 1);
+        } else {
+            mismatched_lines.push(idx + 1);
+        }
+    }
+
+    CodeVerifyResult {
+        matched: mismatched_lines.is_empty(),
+        expected_line_count: expected_lines.len(),
+        received_line_count: received_lines.len(),
+        matched_lines,
+        mismatched_lines,
+    }
+}
+
+#[tauri::command]
+async fn verify_deployed_code(
+    state: State<'_, AppState>,
+    expected: String,
+    request_line: String,
+    end_marker: String,
+    timeout_ms: u64,
+    operation_id: Option<String>,
+) -> Result<CodeVerifyResult, String> {
+    if request_line.trim().is_empty() {
+        return Err("request_line cannot be empty".to_string());
+    }
+    let end_marker = end_marker.trim().to_string();
+    if end_marker.is_empty() {
+        return Err("end_marker cannot be empty".to_string());
+    }
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    let received = send_and_collect_until(
+        &session,
+        &operation,
+        &request_line,
+        &end_marker,
+        Duration::from_millis(timeout_ms),
+    )?;
+
+    Ok(compare_code_lines(&expected, &received))
+}
+
+fn percentile_ms(sorted: &[f64], pct: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((pct / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted.get(rank).copied()
+}
+
+// Reads straight from the port (reader paused) until `expected.len()` bytes arrive or the deadline passes.
+fn read_echo(
+    writer: &mut Box<dyn SerialPort + Send>,
+    expected_len: usize,
+    deadline: std::time::Instant,
+) -> Result<Vec<u8>, String> {
+    let mut echoed = Vec::with_capacity(expected_len);
+    let mut buf = [0_u8; 512];
+    while echoed.len() < expected_len && std::time::Instant::now() < deadline {
+        match writer.read(&mut buf) {
+            Ok(size) => echoed.extend_from_slice(&buf[..size]),
+            Err(error) if error.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(error) => return Err(format!("Serial read failed during benchmark: {error}")),
+        }
+    }
+    Ok(echoed)
+}
+
+// Returns (per-iteration latencies in ms, mismatched, timed out, total elapsed).
+fn run_serial_benchmark(
+    session: &SerialSession,
+    operation: &OperationGuard<'_>,
+    payload_size: usize,
+    iterations: u32,
+) -> Result<(Vec<f64>, u32, u32, Duration), String> {
+    let mut writer = session
+        .writer
+        .lock()
+        .map_err(|_| "Serial writer lock poisoned".to_string())?;
+    let _ = writer.clear(serialport::ClearBuffer::Input);
+    let mut latencies = Vec::with_capacity(iterations as usize);
+    let (mut mismatched, mut timed_out) = (0_u32, 0_u32);
+    let started = std::time::Instant::now();
+    for iteration in 0..iterations {
+        operation.check()?;
+        let payload: Vec<u8> = (0..payload_size)
+            .map(|i| (i as u32 + iteration) as u8)
+            .collect();
+        let sent_at = std::time::Instant::now();
+        writer
+            .write_all(&payload)
+            .and_then(|_| writer.flush())
+            .map_err(|e| format!("Serial write failed during benchmark: {e}"))?;
+        let deadline = sent_at + Duration::from_millis(BENCHMARK_ECHO_TIMEOUT_MS);
+        let echoed = read_echo(&mut writer, payload.len(), deadline)?;
+        if echoed.len() < payload.len() {
+            timed_out += 1;
+            let _ = writer.clear(serialport::ClearBuffer::Input);
+            continue;
+        }
+        latencies.push(sent_at.elapsed().as_secs_f64() * 1000.0);
+        if echoed[..payload.len()] != payload[..] {
+            mismatched += 1;
+        }
+    }
+    Ok((latencies, mismatched, timed_out, started.elapsed()))
+}
+
+// Round-trips a payload through echo firmware or a loopback plug. The reader is parked for
+// the duration so benchmark bytes never reach SERIAL_EVENT or the taps.
+#[tauri::command]
+async fn benchmark_serial(
+    state: State<'_, AppState>,
+    payload_size: usize,
+    iterations: u32,
+    operation_id: Option<String>,
+) -> Result<SerialBenchmarkResult, String> {
+    if payload_size == 0 || payload_size > MAX_BENCHMARK_PAYLOAD {
+        return Err(format!("payload_size must be between 1 and {MAX_BENCHMARK_PAYLOAD}"));
+    }
+    if iterations == 0 || iterations > MAX_BENCHMARK_ITERATIONS {
+        return Err(format!("iterations must be between 1 and {MAX_BENCHMARK_ITERATIONS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    if !session.writes_ready.load(Ordering::SeqCst) {
+        return Err("Device warming up: writes are blocked until serial_ready".to_string());
+    }
+
+    // Nested pauses (a drain or baud switch in progress) stay in effect after this guard drops.
+    let pause = pause_serial_reader(&session)?;
+    let outcome = run_serial_benchmark(&session, &operation, payload_size, iterations);
+    drop(pause);
+    let (mut latencies, mismatched, timed_out, elapsed) = outcome?;
+
+    latencies.sort_by(|a, b| a.total_cmp(b));
+    let completed = latencies.len() as u32;
+    let bytes_per_sec = if elapsed.as_secs_f64() > 0.0 {
+        (completed as usize * payload_size) as f64 / elapsed.as_secs_f64()
+    } else {
+        0.0
+    };
+    Ok(SerialBenchmarkResult {
+        payload_size,
+        iterations,
+        completed,
+        mismatched,
+        timed_out,
+        bytes_per_sec,
+        latency_p50_ms: percentile_ms(&latencies, 50.0),
+        latency_p90_ms: percentile_ms(&latencies, 90.0),
+        latency_p99_ms: percentile_ms(&latencies, 99.0),
+        latency_max_ms: latencies.last().copied(),
+    })
+}
+
+#[tauri::command]
+async fn drain_serial_input(
+    state: State<'_, AppState>,
+    timeout_ms: u64,
+    operation_id: Option<String>,
+) -> Result<SerialDrainResult, String> {
+    if timeout_ms == 0 || timeout_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("timeout_ms must be between 1 and {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    let quiet_for = Duration::from_millis(timeout_ms);
+    let started = std::time::Instant::now();
+    let hard_deadline = started + Duration::from_millis(MAX_SERIAL_CAPTURE_MS);
+
+    // With the reader parked nothing reaches SERIAL_EVENT; stale input is dropped at the OS buffer.
+    let pause = pause_serial_reader(&session)?;
+    let drained = drain_paused_input(&session, &operation, quiet_for, hard_deadline);
+    drop(pause);
+    let (bytes_discarded, quiet) = drained?;
+
+    Ok(SerialDrainResult {
+        bytes_discarded,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        quiet,
+    })
+}
+
 #[tauri::command]
-fn connect_serial(
+async fn deploy_firmware_xmodem(
     app: AppHandle,
     state: State<'_, AppState>,
-    port_name: String,
-    baud_rate: Option<u32>,
-) -> Result<ConnectionStatus, String> {
-    let baud = baud_rate.unwrap_or(115_200);
-
-    let port = serialport::new(&port_name, baud)
-        .timeout(Duration::from_millis(120))
-        .open()
-        .map_err(|error| format!("Failed to open serial port {port_name}: {error}"))?;
-
-    let mut reader = port
-        .try_clone()
-        .map_err(|error| format!("Failed to clone serial reader: {error}"))?;
-
-    let (stop_tx, stop_rx) = mpsc::channel::<()>();
-    let writer: Arc<Mutex<Box<dyn SerialPort + Send>>> =
-        Arc::new(Mutex::new(port as Box<dyn SerialPort + Send>));
-
-    let app_handle = app.clone();
-    thread::spawn(move || {
-        let mut read_buf = [0_u8; 512];
-        let mut pending = String::new();
+    path: String,
+    use_1k: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<XmodemTransferResult, String> {
+    let firmware_path = PathBuf::from(path.trim());
+    let firmware = std::fs::read(&firmware_path)
+        .map_err(|e| format!("Failed to read firmware {}: {e}", firmware_path.display()))?;
+    if firmware.is_empty() {
+        return Err(format!("Firmware file {} is empty", firmware_path.display()));
+    }
+    let block_size = if use_1k.unwrap_or(false) { 1024 } else { 128 };
+    let operation = begin_operation(&state, operation_id)?;
+    let session = active_serial_session(&state)?;
+    let rx = register_byte_tap(&session)?;
+    let mut pending: VecDeque<u8> = VecDeque::new();
+    let start_timeout = Duration::from_millis(XMODEM_START_TIMEOUT_MS);
+    let ack_timeout = Duration::from_millis(XMODEM_ACK_TIMEOUT_MS);
+
+    // The receiver drives the transfer: 'C' asks for CRC-16, NAK for the 8-bit checksum.
+    let crc_mode = loop {
+        match xmodem_read_byte(&rx, &mut pending, &operation, start_timeout)? {
+            Some(XMODEM_CRC_REQUEST) => break true,
+            Some(XMODEM_NAK) => break false,
+            Some(XMODEM_CAN) => {
+                return Err("Receiver cancelled XMODEM transfer before start".to_string())
+            }
+            Some(_) => continue,
+            None => {
+                return Err(format!(
+                    "Timed out after {XMODEM_START_TIMEOUT_MS}ms waiting for receiver to start XMODEM"
+                ))
+            }
+        }
+    };
 
+    let chunks: Vec<&[u8]> = firmware.chunks(block_size).collect();
+    let total_blocks = chunks.len() as u32;
+    let mut total_retries = 0_u32;
+    let mut bytes_sent = 0_u64;
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let block = idx as u32 + 1;
+        let frame = xmodem_block_frame(block as u8, chunk, block_size, crc_mode);
+        let mut attempts = 0_u32;
         loop {
-            if stop_rx.try_recv().is_ok() {
-                break;
+            if attempts > XMODEM_MAX_RETRIES {
+                let _ = write_session_bytes(&session, &[XMODEM_CAN, XMODEM_CAN]);
+                return Err(format!(
+                    "XMODEM block {block} failed after {XMODEM_MAX_RETRIES} retries"
+                ));
             }
-
-            match reader.read(&mut read_buf) {
-                Ok(size) if size > 0 => {
-                    pending.push_str(&String::from_utf8_lossy(&read_buf[..size]));
-                    while let Some(index) = pending.find('\n') {
-                        let raw = pending[..index].trim().to_string();
-                        pending.drain(..=index);
-                        if !raw.is_empty() {
-                            emit_serial_line(&app_handle, raw);
-                        }
-                    }
+            pending.clear();
+            write_session_bytes(&session, &frame)
+                .map_err(|e| format!("XMODEM block {block}: {e}"))?;
+            match xmodem_read_byte(&rx, &mut pending, &operation, ack_timeout)? {
+                Some(XMODEM_ACK) => break,
+                Some(XMODEM_CAN) => {
+                    return Err(format!("Receiver cancelled XMODEM transfer at block {block}"))
                 }
-                Ok(_) => {}
-                Err(error) if error.kind() == std::io::ErrorKind::TimedOut => {}
-                Err(error) => {
-                    emit_serial_line(&app_handle, format!("ERR SERIAL_READ {error}"));
-                    break;
+                _ => {
+                    attempts += 1;
+                    total_retries += 1;
                 }
             }
         }
-    });
+        bytes_sent += chunk.len() as u64;
+        emit_backend_event(
+            &app,
+            XMODEM_PROGRESS_EVENT,
+            XmodemProgressEvent {
+                block,
+                total_blocks,
+                bytes_sent,
+                total_bytes: firmware.len() as u64,
+                retries: total_retries,
+            },
+        );
+    }
 
-    {
-        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
-        stop_session_locked(&mut lock);
-        *lock = Some(SerialSession {
-            writer,
-            stop_tx,
-            port_name: port_name.clone(),
-        });
+    let mut eot_attempts = 0_u32;
+    loop {
+        if eot_attempts > XMODEM_MAX_RETRIES {
+            return Err("XMODEM EOT was not acknowledged".to_string());
+        }
+        pending.clear();
+        write_session_bytes(&session, &[XMODEM_EOT])?;
+        match xmodem_read_byte(&rx, &mut pending, &operation, ack_timeout)? {
+            Some(XMODEM_ACK) => break,
+            _ => eot_attempts += 1,
+        }
     }
 
-    Ok(ConnectionStatus {
-        connected: true,
-        port_name: Some(port_name),
+    append_desktop_audit_log(
+        "serial.xmodem_deploy",
+        &json!({
+            "path": firmware_path.display().to_string(),
+            "bytes": firmware.len(),
+            "blocks": total_blocks,
+            "retries": total_retries
+        }),
+    );
+    Ok(XmodemTransferResult {
+        path: firmware_path.display().to_string(),
+        total_bytes: firmware.len() as u64,
+        blocks: total_blocks,
+        block_size,
+        crc_mode,
+        retries: total_retries,
     })
 }
 
 #[tauri::command]
-fn disconnect_serial(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
-    let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
-    stop_session_locked(&mut lock);
+async fn orchestrator_status(orchestrator_base_url: String) -> Result<Value, String> {
+    orchestrator_request(reqwest::Method::GET, orchestrator_base_url, "/status", None, None).await
+}
 
-    Ok(ConnectionStatus {
-        connected: false,
-        port_name: None,
-    })
+// Mecanum inverse kinematics on a unit chassis: vx forward, vy left, omega counter-clockwise.
+// Returns [front_left, front_right, rear_left, rear_right], scaled down so no wheel exceeds 1.0.
+fn mecanum_wheel_speeds(vx: f32, vy: f32, omega: f32) -> [f32; 4] {
+    let mut wheels = [vx - vy - omega, vx + vy + omega, vx + vy - omega, vx - vy + omega];
+    let peak = wheels.iter().fold(0.0_f32, |acc, w| acc.max(w.abs()));
+    if peak > 1.0 {
+        for wheel in &mut wheels {
+            *wheel /= peak;
+        }
+    }
+    wheels
 }
 
 #[tauri::command]
-fn get_connection_status(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
-    let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
-    if let Some(session) = &*lock {
-        Ok(ConnectionStatus {
-            connected: true,
-            port_name: Some(session.port_name.clone()),
-        })
-    } else {
-        Ok(ConnectionStatus {
-            connected: false,
-            port_name: None,
-        })
+fn compute_wheel_speeds(vx: f32, vy: f32, omega: f32) -> Result<[f32; 4], String> {
+    if !(vx.is_finite() && vy.is_finite() && omega.is_finite()) {
+        return Err("vx, vy and omega must be finite numbers".to_string());
     }
+    Ok(mecanum_wheel_speeds(vx, vy, omega))
 }
 
+// Advanced/diagnostic: writes an arbitrary motor byte with no command validation at all.
+// Meant for bringing up firmware whose command set isn't known yet.
 #[tauri::command]
-fn send_serial_line(state: State<'_, AppState>, line: String) -> Result<(), String> {
-    let lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
-    let Some(session) = &*lock else {
-        return Err("No active serial connection".to_string());
-    };
+async fn send_raw_mecanum(
+    state: State<'_, AppState>,
+    byte: u8,
+    hold_ms: Option<u64>,
+    stop_byte: Option<u8>,
+    transport: Option<String>,
+    stop_repeat: Option<u32>,
+    stop_gap_ms: Option<u64>,
+) -> Result<RawMecanumResult, String> {
+    let transport = transport.unwrap_or_else(|| "serial".to_string());
+    if transport.trim() != "serial" {
+        return Err(format!("Unsupported transport '{}': only serial is available", transport.trim()));
+    }
+    let hold_ms = hold_ms.unwrap_or(0);
+    if hold_ms > MAX_SERIAL_CAPTURE_MS {
+        return Err(format!("hold_ms must be at most {MAX_SERIAL_CAPTURE_MS}"));
+    }
+    let stop_repeat = stop_repeat.unwrap_or(1);
+    if !(1..=MAX_STOP_REPEAT).contains(&stop_repeat) {
+        return Err(format!("stop_repeat must be between 1 and {MAX_STOP_REPEAT}"));
+    }
+    let stop_gap_ms = stop_gap_ms.unwrap_or(DEFAULT_STOP_GAP_MS);
+    if stop_gap_ms > MAX_STOP_GAP_MS {
+        return Err(format!("stop_gap_ms must be at most {MAX_STOP_GAP_MS}"));
+    }
+    ensure_movement_allowed(&state)?;
+    let session = active_serial_session(&state)?;
+    write_session_bytes(&session, &[byte])?;
+    let mut sent = 1;
+    if hold_ms > 0 {
+        thread::sleep(Duration::from_millis(hold_ms));
+    }
+    // Every repeat is attempted even after a failed write; that is the point of sending redundant stops.
+    let mut stops_sent = 0_u32;
+    let mut last_stop_error = None;
+    if let Some(stop) = stop_byte {
+        for attempt in 0..stop_repeat {
+            if attempt > 0 && stop_gap_ms > 0 {
+                thread::sleep(Duration::from_millis(stop_gap_ms));
+            }
+            match write_session_bytes(&session, &[stop]) {
+                Ok(()) => {
+                    stops_sent += 1;
+                    sent += 1;
+                }
+                Err(error) => last_stop_error = Some(error),
+            }
+        }
+    }
+    let stops_failed = if stop_byte.is_some() { stop_repeat - stops_sent } else { 0 };
+    let stop_repeat_used = stop_byte.map(|_| stop_repeat);
+    record_command_log(
+        &state,
+        "serial",
+        "dispatch",
+        json!({
+            "raw_mecanum": hex_bytes(&[byte]),
+            "hold_ms": hold_ms,
+            "stop_byte": stop_byte.map(|b| hex_bytes(&[b])),
+            "stop_repeat": stop_repeat_used,
+            "stop_gap_ms": stop_byte.map(|_| stop_gap_ms),
+            "stops_sent": stops_sent,
+            "stops_failed": stops_failed,
+        }),
+    );
+    append_desktop_audit_log(
+        "serial.raw_mecanum",
+        &json!({
+            "byte": byte,
+            "hold_ms": hold_ms,
+            "stop_byte": stop_byte,
+            "stop_repeat": stop_repeat_used,
+            "stops_sent": stops_sent,
+            "stops_failed": stops_failed
+        }),
+    );
+    if let (Some(error), 0) = (last_stop_error, stops_sent) {
+        return Err(format!("No stop byte was delivered after {stop_repeat} attempts: {error}"));
+    }
+    Ok(RawMecanumResult {
+        bytes_sent: sent,
+        stop_repeat: stop_repeat_used,
+        stops_sent,
+        stops_failed,
+    })
+}
 
-    let mut writer = session
-        .writer
-        .lock()
-        .map_err(|_| "Serial writer lock poisoned".to_string())?;
+fn ensure_movement_allowed(state: &AppState) -> Result<(), String> {
+    if state.movement_frozen.load(Ordering::SeqCst) {
+        return Err(format!("{MOVEMENT_FROZEN} movement is frozen; call unfreeze_movement to resume"));
+    }
+    Ok(())
+}
 
-    writer
-        .write_all(format!("{}\n", line.trim()).as_bytes())
-        .map_err(|error| format!("Serial write failed: {error}"))?;
-    writer
-        .flush()
-        .map_err(|error| format!("Serial flush failed: {error}"))?;
+fn set_movement_frozen(app: &AppHandle, state: &AppState, frozen: bool) -> bool {
+    let was = state.movement_frozen.swap(frozen, Ordering::SeqCst);
+    if was != frozen {
+        emit_backend_event(app, MOVEMENT_FREEZE_EVENT, MovementFreezeEvent { frozen });
+        append_desktop_audit_log("movement.freeze", &json!({ "frozen": frozen }));
+    }
+    frozen
+}
 
-    Ok(())
+#[tauri::command]
+fn freeze_movement(app: AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(set_movement_frozen(&app, &state, true))
 }
 
 #[tauri::command]
-async fn orchestrator_status(orchestrator_base_url: String) -> Result<Value, String> {
-    orchestrator_request(reqwest::Method::GET, orchestrator_base_url, "/status", None, None).await
+fn unfreeze_movement(app: AppHandle, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(set_movement_frozen(&app, &state, false))
 }
 
 #[tauri::command]
 async fn orchestrator_execute_plan(
+    state: State<'_, AppState>,
     orchestrator_base_url: String,
     plan: Value,
     correlation_id: Option<String>,
 ) -> Result<Value, String> {
+    ensure_movement_allowed(&state)?;
+    record_command_log(
+        &state,
+        "orchestrator",
+        "dispatch",
+        json!({ "base_url": orchestrator_base_url.clone(), "plan": plan.clone(), "correlation_id": correlation_id.clone() }),
+    );
     orchestrator_request(
         reqwest::Method::POST,
         orchestrator_base_url,
@@ -1271,9 +4782,10 @@ fn critic_stop(state: State<'_, AppState>) -> Result<CriticStatus, String> {
 }
 
 #[tauri::command]
-fn node_probe(host: String, port: u16) -> Result<NodeProbeStatus, String> {
+fn node_probe(host: String, port: u16, resolve_timeout_ms: Option<u64>) -> Result<NodeProbeStatus, String> {
     let target = format!("{}:{}", host.trim(), port);
-    match probe_daemon_node(&host, port) {
+    let resolve_timeout = Duration::from_millis(resolve_timeout_ms.unwrap_or(DEFAULT_DNS_RESOLVE_TIMEOUT_MS).max(1));
+    match probe_daemon_node(&host, port, resolve_timeout) {
         Ok(summary) => Ok(NodeProbeStatus {
             ok: true,
             host: host.trim().to_string(),
@@ -1513,17 +5025,415 @@ fn orchestrator_process_status(state: State<'_, AppState>) -> Result<Orchestrato
     }
 }
 
+#[tauri::command]
+fn enable_command_log(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<CommandLogStatus, String> {
+    let db_path = match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {e}"))?
+            .join(COMMAND_LOG_FILE),
+    };
+    if let Some(dir) = db_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let conn = rusqlite::Connection::open(&db_path)
+        .map_err(|e| format!("Failed to open command log {}: {e}", db_path.display()))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS command_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ts_ms INTEGER NOT NULL,
+            transport TEXT NOT NULL,
+            event TEXT NOT NULL,
+            detail TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS command_log_ts ON command_log (ts_ms);",
+    )
+    .map_err(|e| format!("Failed to initialize command log {}: {e}", db_path.display()))?;
+
+    let mut lock = state.command_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+    *lock = Some(conn);
+    Ok(CommandLogStatus {
+        enabled: true,
+        path: Some(db_path.display().to_string()),
+    })
+}
+
+#[tauri::command]
+fn disable_command_log(state: State<'_, AppState>) -> Result<CommandLogStatus, String> {
+    let mut lock = state.command_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+    *lock = None;
+    Ok(CommandLogStatus {
+        enabled: false,
+        path: None,
+    })
+}
+
+#[tauri::command]
+fn query_command_log(
+    state: State<'_, AppState>,
+    filters: Option<CommandLogFilter>,
+) -> Result<Vec<CommandLogEntry>, String> {
+    let filters = filters.unwrap_or_default();
+    let lock = state.command_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+    let Some(conn) = &*lock else {
+        return Err("Command log is not enabled".to_string());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, ts_ms, transport, event, detail FROM command_log
+             WHERE (?1 IS NULL OR ts_ms >= ?1)
+               AND (?2 IS NULL OR ts_ms <= ?2)
+               AND (?3 IS NULL OR transport = ?3)
+             ORDER BY ts_ms DESC, id DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| format!("Failed to prepare command log query: {e}"))?;
+    let transport = filters.transport.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let limit = filters.limit.unwrap_or(COMMAND_LOG_DEFAULT_LIMIT).max(1);
+    let rows = stmt
+        .query_map(
+            rusqlite::params![filters.since_ms, filters.until_ms, transport, limit],
+            |row| {
+                let detail: String = row.get(4)?;
+                Ok(CommandLogEntry {
+                    id: row.get(0)?,
+                    ts_ms: row.get(1)?,
+                    transport: row.get(2)?,
+                    event: row.get(3)?,
+                    detail: serde_json::from_str(&detail).unwrap_or(Value::String(detail)),
+                })
+            },
+        )
+        .map_err(|e| format!("Command log query failed: {e}"))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Command log query failed: {e}"))
+}
+
+#[tauri::command]
+fn cancel_operation(state: State<'_, AppState>, operation_id: String) -> Result<bool, String> {
+    let ops = state.operations.lock().map_err(|_| "State lock poisoned".to_string())?;
+    match ops.get(operation_id.trim()) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+// End-of-session cleanup. Every step tolerates the thing already being gone, so calling
+// it twice (or with nothing connected) just reports that there was nothing to do.
+fn save_session_profile(app: &AppHandle, session: &SerialSession, name: &str) -> Result<(), String> {
+    let mut profiles: BTreeMap<String, NamedConnectionProfile> = load_config_map(app, CONNECTION_PROFILES_FILE)?;
+    profiles.insert(
+        name.to_string(),
+        NamedConnectionProfile {
+            name: name.to_string(),
+            transport: "serial".to_string(),
+            port_name: session.port_name.clone(),
+            baud_rate: Some(session.baud_rate.load(Ordering::SeqCst)),
+            options: session.connect_options.clone(),
+        },
+    );
+    store_config_map(app, CONNECTION_PROFILES_FILE, &profiles)
+}
+
+#[tauri::command]
+async fn shutdown_robot(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    stop_line: Option<String>,
+    stop_ack: Option<String>,
+    profile_name: Option<String>,
+) -> Result<ShutdownSummary, String> {
+    let mut summary = ShutdownSummary {
+        stop_sent: false,
+        stop_acknowledged: None,
+        stop_error: None,
+        profile_saved: None,
+        profile_error: None,
+        serial_disconnected: false,
+        orchestrator_stopped: false,
+        command_log_closed: false,
+    };
+    let session = active_serial_session(&state).ok();
+
+    if let (Some(session), Some(stop_line)) = (&session, stop_line.filter(|s| !s.trim().is_empty())) {
+        let ack = stop_ack.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        // Tap before writing so a fast ack can't slip past; a tap failure only costs the ack.
+        let ack_rx = ack.and_then(|_| register_line_tap(session).ok());
+        // stop_sent reflects the write alone; the ack outcome is reported via stop_acknowledged.
+        match write_session_bytes(session, format!("{}\n", stop_line.trim()).as_bytes()) {
+            Ok(()) => {
+                summary.stop_sent = true;
+                record_command_log(&state, "serial", "dispatch", json!({ "line": stop_line.trim(), "shutdown": true }));
+                if let Some(ack) = ack {
+                    let acked = ack_rx.is_some_and(|rx| {
+                        begin_operation(&state, None)
+                            .and_then(|operation| {
+                                collect_until_marker(
+                                    &rx,
+                                    &operation,
+                                    ack,
+                                    Duration::from_millis(SHUTDOWN_STOP_ACK_TIMEOUT_MS),
+                                )
+                            })
+                            .is_ok()
+                    });
+                    summary.stop_acknowledged = Some(acked);
+                }
+            }
+            Err(error) => summary.stop_error = Some(error),
+        }
+    }
+
+    // fd-backed sessions can't be reopened by name, so there's nothing useful to save.
+    if let Some(session) = session.as_ref().filter(|s| !s.port_name.starts_with("fd:")) {
+        let name = profile_name
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| SHUTDOWN_PROFILE_NAME.to_string());
+        // A corrupt or unwritable profiles file must not stop the teardown below.
+        match save_session_profile(&app, session, &name) {
+            Ok(()) => summary.profile_saved = Some(name),
+            Err(error) => summary.profile_error = Some(error),
+        }
+    }
+
+    {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        summary.serial_disconnected = lock.is_some();
+        stop_session_locked(&mut lock);
+        publish_connection_status(&state, ConnectionStatus::default());
+    }
+    if let Some(session) = &session {
+        record_command_log(&state, "serial", "disconnect", json!({ "port_name": session.port_name }));
+    }
+    {
+        let mut lock = state
+            .serial_pattern_stop
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        stop_serial_pattern_locked(&mut lock);
+    }
+    {
+        let mut lock = state
+            .serial_tcp_tap
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        stop_serial_tcp_tap_locked(&mut lock);
+    }
+    {
+        let mut lock = state
+            .orchestrator_proc
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        summary.orchestrator_stopped = lock.is_some();
+        stop_orchestrator_locked(&mut lock);
+    }
+    // Command history is already on disk; dropping the connection closes the SQLite file cleanly.
+    {
+        let mut lock = state.command_log.lock().map_err(|_| "State lock poisoned".to_string())?;
+        summary.command_log_closed = lock.take().is_some();
+    }
+
+    append_desktop_audit_log(
+        "robot.shutdown",
+        &json!({
+            "stop_sent": summary.stop_sent,
+            "stop_acknowledged": summary.stop_acknowledged,
+            "profile_error": summary.profile_error,
+            "serial_disconnected": summary.serial_disconnected,
+            "orchestrator_stopped": summary.orchestrator_stopped
+        }),
+    );
+    Ok(summary)
+}
+
+fn stdout_mirror_from_env() -> bool {
+    std::env::var(SERIAL_STDOUT_ENV)
+        .map(|v| v.trim() == "1")
+        .unwrap_or(false)
+}
+
+// Puts every user-adjustable setting and cache back to its startup value.
+fn reset_tunable_state(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    *state
+        .last_connect_failure
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = None;
+    state.event_log.lock().map_err(|_| "State lock poisoned".to_string())?.clear();
+    *state
+        .serial_event_name
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = None;
+    state.unified_events.store(false, Ordering::SeqCst);
+    state.stdout_mirror.store(stdout_mirror_from_env(), Ordering::SeqCst);
+    state.serial_aliases.lock().map_err(|_| "State lock poisoned".to_string())?.clear();
+    *state
+        .serial_alias_prefix
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())? = None;
+    // Last, so the unfreeze event goes out on the default channel and lands in the fresh log.
+    set_movement_frozen(app, state, false);
+    Ok(())
+}
+
+#[tauri::command]
+fn reset_backend(app: AppHandle, state: State<'_, AppState>, confirm: bool) -> Result<BackendResetStatus, String> {
+    if !confirm {
+        return Err("reset_backend requires confirm=true".to_string());
+    }
+
+    let serial_disconnected = {
+        let mut lock = state.session.lock().map_err(|_| "State lock poisoned".to_string())?;
+        let was_connected = lock.is_some();
+        stop_session_locked(&mut lock);
+        publish_connection_status(&state, ConnectionStatus::default());
+        was_connected
+    };
+    let orchestrator_stopped = {
+        let mut lock = state
+            .orchestrator_proc
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        let was_running = lock.is_some();
+        stop_orchestrator_locked(&mut lock);
+        was_running
+    };
+    {
+        let mut lock = state
+            .serial_pattern_stop
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        stop_serial_pattern_locked(&mut lock);
+    }
+    {
+        let mut lock = state
+            .serial_tcp_tap
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        stop_serial_tcp_tap_locked(&mut lock);
+    }
+    {
+        let mut lock = state.port_watch.lock().map_err(|_| "State lock poisoned".to_string())?;
+        stop_port_watch_locked(&mut lock);
+    }
+    {
+        let ops = state.operations.lock().map_err(|_| "State lock poisoned".to_string())?;
+        for flag in ops.values() {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+    let critic_cleared = {
+        let mut lock = state
+            .critic_session
+            .lock()
+            .map_err(|_| "State lock poisoned".to_string())?;
+        lock.take().is_some()
+    };
+    // Dropping the connection closes the SQLite file; logging stays off until re-enabled.
+    let command_log_closed = state
+        .command_log
+        .lock()
+        .map_err(|_| "State lock poisoned".to_string())?
+        .take()
+        .is_some();
+    reset_tunable_state(&app, &state)?;
+
+    append_desktop_audit_log(
+        "backend.reset",
+        &json!({
+            "serial_disconnected": serial_disconnected,
+            "orchestrator_stopped": orchestrator_stopped,
+            "critic_cleared": critic_cleared,
+            "command_log_closed": command_log_closed
+        }),
+    );
+    Ok(BackendResetStatus {
+        serial_disconnected,
+        orchestrator_stopped,
+        critic_cleared,
+        command_log_closed,
+    })
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(AppState::default())
+        .manage(AppState {
+            stdout_mirror: AtomicBool::new(stdout_mirror_from_env()),
+            ..Default::default()
+        })
+        .setup(|app| {
+            spawn_resume_watch(app.handle().clone());
+            Ok(())
+        })
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             list_serial_ports,
+            subscribe_port_changes,
+            unsubscribe_port_changes,
+            refresh_serial_subsystem,
             connect_serial,
+            connect_serial_fd,
+            rebind_serial,
             disconnect_serial,
+            get_connect_diagnostics,
+            get_serial_driver_info,
+            diagnose_port,
+            get_serial_buffer_status,
+            get_serial_uptime,
+            get_serial_parity_policy,
+            get_serial_data_profile,
+            set_stdout_mirror,
+            set_unified_events,
+            get_event_log,
+            clear_event_log,
+            compute_wheel_speeds,
+            send_raw_mecanum,
+            freeze_movement,
+            unfreeze_movement,
+            set_serial_event_name,
+            export_config,
+            import_config,
+            probe_supported_bauds,
+            save_device_profile,
+            list_device_profiles,
+            delete_device_profile,
+            save_named_profile,
+            list_named_profiles,
+            delete_named_profile,
+            connect_profile,
             get_connection_status,
             send_serial_line,
+            send_serial_frames,
+            define_serial_alias,
+            remove_serial_alias,
+            list_serial_aliases,
+            set_serial_alias_prefix,
+            inject_serial_lines,
+            set_serial_delimiter,
+            set_serial_read_timeout,
+            start_serial_pattern,
+            stop_serial_pattern,
+            start_serial_tcp_tap,
+            stop_serial_tcp_tap,
+            capture_serial_window,
+            read_device_config,
+            negotiate_serial_baud,
+            deploy_firmware_xmodem,
+            drain_serial_input,
+            benchmark_serial,
+            verify_deployed_code,
+            serial_interact,
             orchestrator_status,
             orchestrator_execute_plan,
             orchestrator_stop,
@@ -1536,10 +5446,77 @@ pub fn run() {
             write_debug_log,
             read_debug_log,
             read_desktop_audit_log,
+            export_diagnostics,
             orchestrator_spawn,
             orchestrator_stop_process,
-            orchestrator_process_status
+            orchestrator_process_status,
+            enable_command_log,
+            disable_command_log,
+            query_command_log,
+            cancel_operation,
+            reset_backend,
+            shutdown_robot
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_wheels(actual: [f32; 4], expected: [f32; 4]) {
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-6, "expected {expected:?}, got {actual:?}");
+        }
+    }
+
+    #[test]
+    fn wheel_speeds_pure_forward() {
+        assert_wheels(mecanum_wheel_speeds(1.0, 0.0, 0.0), [1.0, 1.0, 1.0, 1.0]);
+        assert_wheels(mecanum_wheel_speeds(-0.5, 0.0, 0.0), [-0.5, -0.5, -0.5, -0.5]);
+    }
+
+    #[test]
+    fn wheel_speeds_pure_strafe_left() {
+        assert_wheels(mecanum_wheel_speeds(0.0, 1.0, 0.0), [-1.0, 1.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn wheel_speeds_pure_rotate_ccw() {
+        assert_wheels(mecanum_wheel_speeds(0.0, 0.0, 1.0), [-1.0, 1.0, -1.0, 1.0]);
+    }
+
+    #[test]
+    fn wheel_speeds_normalize_when_combined_exceeds_one() {
+        assert_wheels(mecanum_wheel_speeds(1.0, 1.0, 0.0), [0.0, 1.0, 1.0, 0.0]);
+        assert_wheels(mecanum_wheel_speeds(1.0, 0.5, 0.5), [0.0, 1.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn verify_matches_indented_program_after_reader_trim() {
+        let expected = "def loop():\n    forward(1)\n\n    if blocked:\n        stop()\r\n";
+        // What the reader thread delivers: every line trimmed on both ends.
+        let received: Vec<String> = ["def loop():", "forward(1)", "if blocked:", "stop()"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = compare_code_lines(expected, &received);
+        assert!(result.matched, "mismatched lines: {:?}", result.mismatched_lines);
+        assert_eq!(result.expected_line_count, 4);
+        assert_eq!(result.matched_lines, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn verify_reports_changed_and_missing_lines() {
+        let received: Vec<String> = vec!["a()".to_string(), "c()".to_string()];
+        let result = compare_code_lines("a()\n  b()\n  d()\n", &received);
+        assert!(!result.matched);
+        assert_eq!(result.mismatched_lines, vec![2, 3]);
+    }
+
+    #[test]
+    fn wheel_speeds_below_one_are_not_scaled() {
+        assert_wheels(mecanum_wheel_speeds(0.3, 0.2, 0.1), [0.0, 0.6, 0.4, 0.2]);
+    }
+}